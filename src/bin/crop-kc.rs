@@ -0,0 +1,596 @@
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use crop_coefficients::{
+    calculate_gdd, load_climate_means_from, load_kc_curves_from, load_land_covers_from,
+    load_weather_csv, write_metadata_sidecar, write_records, ClimateMeans, CropCoefficientsGs,
+    CurveAxis, DailyRecord, GapFill, KcModel, LandCover, OutputFormat, OutputMetadata, SpeedUnit,
+    WeatherCsvLayout, WeatherSeries,
+};
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// Crop coefficients, growing degree days and crop ET from the FAO-56 crop table and station weather files.
+#[derive(Parser)]
+#[command(name = "crop-kc", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the crops, open water surfaces and Kc curves of the crop table
+    ListCrops {
+        /// Crop table file
+        #[arg(long, default_value = "fao56.toml")]
+        table: PathBuf,
+    },
+    /// Print the daily Kc curve of a crop from planting to the end of the season
+    Curve {
+        /// Crop, open water or Kc curve name as in the crop table
+        #[arg(long)]
+        crop: String,
+        /// Planting date (YYYY-MM-DD), defaults to the planting date of the crop table
+        #[arg(long)]
+        planting_date: Option<NaiveDate>,
+        /// Wind speed at 2 m in m/s for the climate adjustment
+        #[arg(long)]
+        wind_speed: Option<f32>,
+        /// Minimum relative humidity in % for the climate adjustment
+        #[arg(long)]
+        rh_min: Option<f32>,
+        /// Crop table file
+        #[arg(long, default_value = "fao56.toml")]
+        table: PathBuf,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Accumulate growing degree days (°C) from a weather CSV
+    Gdd {
+        #[command(flatten)]
+        weather: WeatherArgs,
+        /// Base temperature in °C
+        #[arg(long, default_value_t = 10.0)]
+        base_temp: f32,
+        /// First day of the accumulation (YYYY-MM-DD), defaults to the first day of the file
+        #[arg(long)]
+        start: Option<NaiveDate>,
+        /// Crop name written in the crop column
+        #[arg(long, default_value = "")]
+        crop: String,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Combine the ETo of a weather CSV with the Kc of a crop into daily crop ET (ETc)
+    Etc {
+        /// Crop, open water or Kc curve name as in the crop table
+        #[arg(long)]
+        crop: String,
+        /// Planting date (YYYY-MM-DD), defaults to the planting date of the crop table
+        #[arg(long)]
+        planting_date: Option<NaiveDate>,
+        #[command(flatten)]
+        weather: WeatherArgs,
+        /// Crop table file
+        #[arg(long, default_value = "fao56.toml")]
+        table: PathBuf,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+#[derive(clap::Args)]
+struct WeatherArgs {
+    /// Daily weather CSV file
+    #[arg(long)]
+    weather: PathBuf,
+    /// Layout of the weather file, generic files have the columns date, max_temp and min_temp in °C and optionally
+    /// wind_speed (m/s at 2 m), rh_min (%), dew_point (°C), precipitation (mm) and eto (mm)
+    #[arg(long, value_enum, default_value = "generic")]
+    layout: Layout,
+    /// Gap filling of missing weather values
+    #[arg(long, value_enum, default_value = "linear")]
+    gap_fill: GapFillMethod,
+}
+
+#[derive(clap::Args)]
+struct OutputArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value = "csv")]
+    format: Format,
+    /// Output file, defaults to standard output
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Write the metadata of a CSV output file to a JSON sidecar file <output>.meta.json
+    #[arg(long)]
+    metadata: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Layout {
+    Generic,
+    Hprcc,
+    Awdn,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GapFillMethod {
+    None,
+    Linear,
+    Climatology,
+}
+
+// Row of the crop list, the stage Kc values are only given for the crops
+#[derive(Serialize)]
+struct CropRow {
+    name: String,
+    kind: &'static str, // "crop", "open_water" or "kc_curve"
+    planting_date: NaiveDate,
+    season_days: Option<u16>,
+    k_ini: Option<f32>,
+    k_mid: Option<f32>,
+    k_end: Option<f32>,
+    height_m: Option<f64>,
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    match cli.command {
+        Command::ListCrops { table } => {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+            for row in crop_rows(&table)? {
+                writer.serialize(row)?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+        Command::Curve {
+            crop,
+            planting_date,
+            wind_speed,
+            rh_min,
+            table,
+            output,
+        } => {
+            let model = load_model(&table, &crop, planting_date)?;
+            // The climate of the crop table stands in for the wind speed and humidity not given
+            let climate = load_climate_means_from(&table)?;
+            let records = curve_records(
+                model.as_ref(),
+                wind_speed.or(Some(climate.wind_speed)),
+                rh_min.or(Some(climate.rh_min)),
+            );
+            let metadata =
+                OutputMetadata::new(climate).with_crop_table(&table.display().to_string());
+            write_output(&records, &metadata, &output)
+        }
+        Command::Gdd {
+            weather,
+            base_temp,
+            start,
+            crop,
+            output,
+        } => {
+            let series = load_weather(&weather)?;
+            let records = gdd_records(&series, &crop, base_temp, start);
+            let metadata = OutputMetadata::new(ClimateMeans::default())
+                .with_weather_file(&weather.weather.display().to_string());
+            write_output(&records, &metadata, &output)
+        }
+        Command::Etc {
+            crop,
+            planting_date,
+            weather,
+            table,
+            output,
+        } => {
+            let model = load_model(&table, &crop, planting_date)?;
+            let climate = load_climate_means_from(&table)?;
+            let series = load_weather(&weather)?;
+            let metadata = OutputMetadata::new(climate)
+                .with_crop_table(&table.display().to_string())
+                .with_weather_file(&weather.weather.display().to_string());
+            write_output(
+                &etc_records(model.as_ref(), &series, &climate),
+                &metadata,
+                &output,
+            )
+        }
+    }
+}
+
+// Rows of every name `load_model` accepts, sorted by name
+fn crop_rows(table: &Path) -> Result<Vec<CropRow>, Box<dyn Error>> {
+    let mut rows: Vec<CropRow> = load_land_covers_from(table)?
+        .values()
+        .map(|land_cover| match land_cover {
+            LandCover::Vegetation(cc) => crop_row(cc),
+            LandCover::OpenWater(water) => CropRow {
+                name: water.name.clone(),
+                kind: "open_water",
+                planting_date: water.start_date,
+                season_days: None,
+                k_ini: None,
+                k_mid: None,
+                k_end: None,
+                height_m: None,
+            },
+        })
+        .collect();
+    rows.extend(
+        load_kc_curves_from(table)?
+            .values()
+            .filter(|curve| curve.axis != CurveAxis::NormalizedGdd)
+            .map(|curve| CropRow {
+                name: curve.crop_name.clone(),
+                kind: "kc_curve",
+                planting_date: curve.planting_date,
+                season_days: curve
+                    .season_end()
+                    .map(|end| (end - curve.planting_date).num_days() as u16),
+                k_ini: None,
+                k_mid: None,
+                k_end: None,
+                height_m: Some(curve.crop_height),
+            }),
+    );
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(rows)
+}
+
+fn crop_row(cc: &CropCoefficientsGs) -> CropRow {
+    CropRow {
+        name: cc.crop_name.clone(),
+        kind: "crop",
+        planting_date: cc.planting_date,
+        season_days: Some(cc.late_end_kc.days),
+        k_ini: Some(cc.initial_end_kc.kc),
+        k_mid: Some(cc.mid_end_kc.kc),
+        k_end: Some(cc.late_end_kc.kc),
+        height_m: Some(cc.crop_height),
+    }
+}
+
+// Kc model of a crop or open water of the crop table or of a Kc curve, with the planting date moved if given
+fn load_model(
+    table: &Path,
+    name: &str,
+    planting_date: Option<NaiveDate>,
+) -> Result<Box<dyn KcModel>, Box<dyn Error>> {
+    if let Some(land_cover) = load_land_covers_from(table)?.remove(name) {
+        let model = match land_cover {
+            LandCover::Vegetation(mut cc) => {
+                if let Some(planting_date) = planting_date {
+                    cc.planting_date = planting_date;
+                }
+                LandCover::Vegetation(cc)
+            }
+            LandCover::OpenWater(mut water) => {
+                if let Some(planting_date) = planting_date {
+                    water.start_date = planting_date;
+                }
+                LandCover::OpenWater(water)
+            }
+        };
+        return Ok(Box::new(model));
+    }
+
+    let mut curve = load_kc_curves_from(table)?.remove(name).ok_or(format!(
+        "Crop {} not found in {}.",
+        name,
+        table.display()
+    ))?;
+    // Curves on the normalized GDD axis need cumulative GDD and cannot give the Kc on a date
+    if curve.axis == CurveAxis::NormalizedGdd {
+        return Err(format!(
+            "Kc curve {} is on the normalized GDD axis and has no Kc by date.",
+            name
+        )
+        .into());
+    }
+    if let Some(planting_date) = planting_date {
+        curve.planting_date = planting_date;
+    }
+    Ok(Box::new(curve))
+}
+
+// Record of the Kc on a date, with the breakdown of the calculation where the model provides one
+fn kc_record(
+    model: &dyn KcModel,
+    date: NaiveDate,
+    wind_speed: Option<f32>,
+    rh_min: Option<f32>,
+) -> DailyRecord {
+    match model.kc_result_from_date(date, wind_speed, rh_min, None) {
+        Some(result) => DailyRecord::from_kc_result(date, &result),
+        None => DailyRecord {
+            growth_stage: model.growth_stage_on(date),
+            kc: Some(model.coefficient_from_date(date, wind_speed, rh_min, None)),
+            ..DailyRecord::new(date, model.crop_name())
+        },
+    }
+}
+
+// Last day of the curve output, a year for models without a season end such as open water
+fn curve_end(model: &dyn KcModel) -> NaiveDate {
+    model
+        .season_end()
+        .unwrap_or(model.planting_date() + chrono::Duration::days(364))
+}
+
+fn curve_records(
+    model: &dyn KcModel,
+    wind_speed: Option<f32>,
+    rh_min: Option<f32>,
+) -> Vec<DailyRecord> {
+    let end = curve_end(model);
+    model
+        .planting_date()
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| kc_record(model, date, wind_speed, rh_min))
+        .collect()
+}
+
+fn gdd_records(
+    series: &WeatherSeries,
+    crop: &str,
+    base_temp: f32,
+    start: Option<NaiveDate>,
+) -> Vec<DailyRecord> {
+    let mut cumulative_gdd = 0.0;
+    series
+        .records
+        .iter()
+        .filter(|r| start.is_none_or(|start| r.date >= start))
+        .map(|r| {
+            let gdd = calculate_gdd(r.max_temp, r.min_temp, base_temp);
+            cumulative_gdd += gdd;
+            DailyRecord::new(r.date, crop).with_gdd(gdd, cumulative_gdd)
+        })
+        .collect()
+}
+
+// Daily ETc of the season on the days of the weather series with ETo, with the climate means on days without wind speed or
+// humidity
+fn etc_records(
+    model: &dyn KcModel,
+    series: &WeatherSeries,
+    climate: &ClimateMeans,
+) -> Vec<DailyRecord> {
+    let season_end = model.season_end();
+    series
+        .records
+        .iter()
+        .filter(|r| r.date >= model.planting_date() && season_end.is_none_or(|end| r.date <= end))
+        .filter_map(|r| {
+            let eto = r.eto?;
+            let record = kc_record(
+                model,
+                r.date,
+                r.wind_speed_2m().or(Some(climate.wind_speed)),
+                r.min_relative_humidity().or(Some(climate.rh_min)),
+            );
+            Some(record.with_eto(eto))
+        })
+        .collect()
+}
+
+fn load_weather(args: &WeatherArgs) -> Result<WeatherSeries, Box<dyn Error>> {
+    let layout = match args.layout {
+        Layout::Generic => generic_layout(&args.weather)?,
+        Layout::Hprcc => WeatherCsvLayout::hprcc(),
+        Layout::Awdn => WeatherCsvLayout::awdn(),
+    };
+    let gap_fill = match args.gap_fill {
+        GapFillMethod::None => GapFill::None,
+        GapFillMethod::Linear => GapFill::Linear { max_gap_days: 3 },
+        GapFillMethod::Climatology => GapFill::Climatology,
+    };
+
+    let series = load_weather_csv(&args.weather, &layout, gap_fill)?;
+    if !series.issues.is_empty() {
+        eprintln!(
+            "{} quality control flags in {}.",
+            series.issues.len(),
+            args.weather.display()
+        );
+    }
+    Ok(series)
+}
+
+// Generic layout with the optional columns present in the header of the file
+fn generic_layout(path: &Path) -> Result<WeatherCsvLayout, Box<dyn Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)?;
+    let header = reader.headers()?.clone();
+    let has = |name: &str| header.iter().any(|h| h == name);
+
+    let mut layout = WeatherCsvLayout::new("date", "max_temp", "min_temp");
+    if has("wind_speed") {
+        layout = layout.with_wind_speed("wind_speed", SpeedUnit::MetersPerSecond, 2.0);
+    }
+    if has("rh_min") {
+        layout = layout.with_rh_min("rh_min");
+    }
+    if has("dew_point") {
+        layout = layout.with_dew_point("dew_point");
+    }
+    if has("precipitation") {
+        layout = layout.with_precipitation("precipitation");
+    }
+    if has("eto") {
+        layout = layout.with_eto("eto");
+    }
+    Ok(layout)
+}
+
+// Writes the records to the output file or standard output, with the metadata in the file or, for CSV on request, in a
+// sidecar file
+fn write_output(
+    records: &[DailyRecord],
+    metadata: &OutputMetadata,
+    output: &OutputArgs,
+) -> Result<(), Box<dyn Error>> {
+    let format = match output.format {
+        Format::Csv => OutputFormat::Csv,
+        Format::Jsonl => OutputFormat::JsonLines,
+        Format::Parquet => OutputFormat::Parquet,
+    };
+    if output.metadata && format == OutputFormat::Csv {
+        let path = output
+            .output
+            .as_ref()
+            .ok_or("The metadata sidecar file needs an output file.")?;
+        write_metadata_sidecar(fs::File::create(sidecar_path(path))?, metadata)?;
+    }
+    let writer: Box<dyn Write + Send> = match &output.output {
+        Some(path) => Box::new(fs::File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    write_records(writer, records, metadata, format)
+}
+
+// Path of the metadata sidecar file of an output file, the output path with .meta.json appended
+fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".meta.json");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crop_coefficients::{GrowthStage, WeatherRecord};
+
+    fn corn() -> CropCoefficientsGs {
+        CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        )
+    }
+
+    #[test]
+    fn test_curve_rows_cover_the_season() {
+        let rows = curve_records(&corn(), None, None);
+
+        assert_eq!(rows.len(), 121);
+        assert_eq!(rows[0].growth_stage, Some(GrowthStage::Initial));
+        assert_eq!(rows[0].kc, Some(0.3));
+        assert_eq!(
+            rows[120].date,
+            NaiveDate::from_ymd_opt(2023, 8, 18).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_curve_rows_of_open_water_and_kc_curves() {
+        let table = Path::new("fao56.toml");
+        let reservoir = load_model(table, "reservoir", None).unwrap();
+        let rows = curve_records(reservoir.as_ref(), None, None);
+        assert_eq!(rows.len(), 365);
+        assert_eq!(rows[0].growth_stage, None);
+        assert!(rows[0].kc.is_some());
+
+        let planting_date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let curve = load_model(table, "corn_percent_of_season", Some(planting_date)).unwrap();
+        let rows = curve_records(curve.as_ref(), None, None);
+        assert_eq!(rows[0].date, planting_date);
+        assert_eq!(rows[0].tabular_kc, None);
+
+        assert!(load_model(table, "unknown", None).is_err());
+    }
+
+    #[test]
+    fn test_gdd_and_etc_rows() {
+        let start = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let records = (0..3)
+            .map(|day| {
+                WeatherRecord::new(start + chrono::Duration::days(day), 30.0, 20.0).with_eto(6.0)
+            })
+            .collect();
+        let series = WeatherSeries {
+            records,
+            issues: Vec::new(),
+        };
+
+        let gdd = gdd_records(&series, "corn", 10.0, start.succ_opt());
+        assert_eq!(gdd.len(), 2);
+        assert_eq!(gdd[1].cumulative_gdd, Some(30.0));
+
+        let etc = etc_records(&corn(), &series, &ClimateMeans::default());
+        assert_eq!(etc.len(), 3);
+        assert!((etc[0].etc.unwrap() - etc[0].kc.unwrap() * 6.0).abs() < 0.0001);
+        assert_eq!(etc[0].growth_stage, Some(GrowthStage::Mid));
+
+        // Without wind speed in the weather file the windier climate of the crop table raises Kc_mid
+        let windy = etc_records(&corn(), &series, &ClimateMeans::new(4.0, 45.0));
+        assert!(windy[0].kc > etc[0].kc);
+    }
+
+    #[test]
+    fn test_generic_layout_reads_quoted_header() {
+        let path = std::env::temp_dir().join("crop_kc_test_generic_layout.csv");
+        fs::write(
+            &path,
+            "\"date\",\"max_temp\",\"min_temp\",\"eto\"\n2023-07-01,30,15,6.0\n",
+        )
+        .unwrap();
+        let args = WeatherArgs {
+            weather: path.clone(),
+            layout: Layout::Generic,
+            gap_fill: GapFillMethod::None,
+        };
+
+        let series = load_weather(&args).unwrap();
+        assert_eq!(series.records[0].eto, Some(6.0));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_load_model_rejects_normalized_gdd_curves() {
+        let path = std::env::temp_dir().join("crop_kc_test_normalized_gdd.toml");
+        let table = fs::read_to_string("fao56.toml").unwrap()
+            + "[curves.corn_gdd]\nname = \"corn_gdd\"\naxis = \"normalized_gdd\"\ninterpolation = \"linear\"\n\
+               planting_date = \"2023-04-20\"\nheight_m = 2.0\npoints = [[0.0, 0.3], [0.5, 1.2], [1.0, 0.6]]\n";
+        fs::write(&path, table).unwrap();
+
+        let error = load_model(&path, "corn_gdd", None).err().unwrap();
+        assert!(error.to_string().contains("normalized GDD axis"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_crop_rows_list_every_model_name() {
+        let table = Path::new("fao56.toml");
+        let rows = crop_rows(table).unwrap();
+
+        assert!(rows.iter().any(|row| row.kind == "open_water"));
+        assert!(rows.iter().any(|row| row.kind == "kc_curve"));
+        for row in &rows {
+            assert!(load_model(table, &row.name, None).is_ok(), "{}", row.name);
+        }
+    }
+}
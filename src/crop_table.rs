@@ -0,0 +1,236 @@
+use crate::kcc_gs::CropCoefficientsGs;
+
+// Basal crop coefficients (Kcb) of the initial, mid and late stages (FAO-56 Table 17), the transpiration part of Kc for a dry
+// soil surface.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BasalKc {
+    pub ini: f32,
+    pub mid: f32,
+    pub end: f32,
+}
+
+impl BasalKc {
+    /// Creates a new `BasalKc`. Panics if any Kcb value exceeds 2.
+    pub fn new(ini: f32, mid: f32, end: f32) -> Self {
+        if ini > 2.0 || mid > 2.0 || end > 2.0 {
+            panic!("Kcb cannot exceed 2.");
+        }
+
+        BasalKc { ini, mid, end }
+    }
+}
+
+// Range of the maximum effective rooting depth of a crop in meters (FAO-56 Table 22), the smaller value for soils with layers
+// or a water table that restrict roots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootDepth {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl RootDepth {
+    /// Creates a new `RootDepth` with the range in meters. Panics if the range is negative or reversed.
+    pub fn new(min: f32, max: f32) -> Self {
+        if min < 0.0 || max < min {
+            panic!("Root depth range must be 0 or more with min no more than max.");
+        }
+
+        RootDepth { min, max }
+    }
+
+    /// Returns the middle of the range in meters.
+    pub fn mean(&self) -> f32 {
+        (self.min + self.max) / 2.0
+    }
+}
+
+/// Adjusts the tabulated depletion fraction p for the crop ET (FAO-56 Table 22 footnote), p = p_table + 0.04 (5 - ETc).
+///
+/// # Parameters
+///
+/// - `p`: The tabulated depletion fraction for an ETc of 5 mm/day.
+/// - `etc`: The crop ET in mm/day.
+///
+/// # Returns
+///
+/// The adjusted depletion fraction, between 0.1 and 0.8.
+pub fn adjusted_depletion_fraction(p: f32, etc: f32) -> f32 {
+    (p + 0.04 * (5.0 - etc)).clamp(0.1, 0.8)
+}
+
+/// Finds the variant of a crop for a region. Region labels are "Region" or "Region, qualifier", the region matches the whole
+/// label or its region part case insensitively, preferring a whole label.
+///
+/// # Parameters
+///
+/// - `variants`: The `CropCoefficientsGs` variants of a crop, as loaded by `load_crop_variants`.
+/// - `region`: The region, for example "Great Plains" or "Great Plains, Idaho".
+///
+/// # Returns
+///
+/// The first variant with the whole region label, or else the first variant of the region, if any.
+pub fn find_variant<'a>(
+    variants: &'a [CropCoefficientsGs],
+    region: &str,
+) -> Option<&'a CropCoefficientsGs> {
+    let region = region.trim();
+    let labeled = || {
+        variants
+            .iter()
+            .filter_map(|cc| Some((cc, cc.region.as_deref()?)))
+    };
+
+    labeled()
+        .find(|(_, label)| label.eq_ignore_ascii_case(region))
+        .or_else(|| labeled().find(|(_, label)| region_of(label).eq_ignore_ascii_case(region)))
+        .map(|(cc, _)| cc)
+}
+
+// Region part of a "Region, qualifier" label.
+fn region_of(label: &str) -> &str {
+    label.split(',').next().unwrap_or(label).trim()
+}
+
+impl CropCoefficientsGs {
+    /// Returns the crop coefficients with the basal crop coefficients of the crop, see `basal_coefficients`.
+    pub fn with_basal_kc(mut self, basal_kc: BasalKc) -> CropCoefficientsGs {
+        self.basal_kc = Some(basal_kc);
+        self
+    }
+
+    /// Returns the crop coefficients with the maximum rooting depth range of the crop.
+    pub fn with_root_depth(mut self, root_depth: RootDepth) -> CropCoefficientsGs {
+        self.root_depth = Some(root_depth);
+        self
+    }
+
+    /// Returns the crop coefficients with the tabulated depletion fraction p of the crop. Panics if p is not between 0 and 1.
+    pub fn with_depletion_fraction(mut self, p: f32) -> CropCoefficientsGs {
+        if !(0.0..=1.0).contains(&p) {
+            panic!("Depletion fraction must be between 0 and 1.");
+        }
+        self.depletion_fraction = Some(p);
+        self
+    }
+
+    /// Returns a copy of the crop coefficients with the Kcb values in place of the Kc values, so the basal curve can be
+    /// queried like the single Kc curve. The end of the development stage ramps up to Kcb_mid.
+    pub fn basal_coefficients(&self) -> Option<CropCoefficientsGs> {
+        let basal_kc = self.basal_kc?;
+
+        let mut basal = self.clone();
+        basal.initial_end_kc.kc = basal_kc.ini;
+        basal.development_end_kc.kc = basal_kc.mid;
+        basal.mid_end_kc.kc = basal_kc.mid;
+        basal.late_end_kc.kc = basal_kc.end;
+        Some(basal)
+    }
+
+    /// Returns the depletion fraction p of the crop adjusted for the crop ET in mm/day, see `adjusted_depletion_fraction`.
+    pub fn depletion_fraction_at(&self, etc: f32) -> Option<f32> {
+        self.depletion_fraction
+            .map(|p| adjusted_depletion_fraction(p, etc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kcc_gs::{
+        load_crop_coefficients, load_crop_coefficients_for_region, load_crop_variants,
+    };
+    use crate::test_fixtures::corn;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_adjusted_depletion_fraction() {
+        assert!((adjusted_depletion_fraction(0.55, 5.0) - 0.55).abs() < 0.0001);
+        assert!((adjusted_depletion_fraction(0.55, 8.0) - 0.43).abs() < 0.0001);
+        assert!((adjusted_depletion_fraction(0.55, 2.0) - 0.67).abs() < 0.0001);
+        assert_eq!(adjusted_depletion_fraction(0.2, 12.0), 0.1);
+    }
+
+    #[test]
+    fn test_crop_table() {
+        let crops = load_crop_coefficients().unwrap();
+        assert_eq!(crops.len(), 91);
+
+        let tomato = &crops["tomato"];
+        assert_eq!(tomato.region.as_deref(), Some("Arid region"));
+        assert_eq!(tomato.late_end_kc.days, 135);
+        assert_eq!(tomato.basal_kc, Some(BasalKc::new(0.15, 1.10, 0.70)));
+        assert_eq!(tomato.root_depth, Some(RootDepth::new(0.7, 1.5)));
+        assert_eq!(tomato.depletion_fraction, Some(0.40));
+
+        // Every crop has a Kcb curve below its Kc curve at mid-season
+        for cc in crops.values().filter(|cc| cc.basal_kc.is_some()) {
+            let basal = cc.basal_coefficients().unwrap();
+            assert!(basal.mid_end_kc.kc <= cc.mid_end_kc.kc, "{}", cc.crop_name);
+        }
+    }
+
+    #[test]
+    fn test_regional_variants() {
+        let variants = load_crop_variants().unwrap();
+        let corn = &variants["corn"];
+        assert_eq!(corn.len(), 8);
+        assert_eq!(corn[0].region, None);
+        assert_eq!(corn[0].late_end_kc.days, 120);
+
+        // The region part of a label selects the first variant of the region
+        let plains = find_variant(corn, "great plains").unwrap();
+        assert_eq!(plains.region.as_deref(), Some("Great Plains, Idaho"));
+        assert_eq!(plains.late_end_kc.days, 170);
+        assert_eq!(
+            plains.planting_date,
+            NaiveDate::from_ymd_opt(2023, 4, 15).unwrap()
+        );
+        assert_eq!(plains.mid_end_kc.kc, corn[0].mid_end_kc.kc);
+        assert!(std::ptr::eq(
+            find_variant(corn, "Great Plains, Idaho").unwrap(),
+            plains
+        ));
+        assert_eq!(
+            find_variant(corn, "Mediterranean")
+                .unwrap()
+                .late_end_kc
+                .days,
+            150
+        );
+        assert!(find_variant(corn, "Antarctica").is_none());
+        assert!(find_variant(corn, "Idaho").is_none());
+
+        // A whole label is preferred over the region part
+        let sugarbeet = &variants["sugarbeet"];
+        let june = find_variant(sugarbeet, "California, June planting").unwrap();
+        assert_eq!(june.late_end_kc.days, 155);
+        assert_eq!(
+            find_variant(sugarbeet, "California")
+                .unwrap()
+                .late_end_kc
+                .days,
+            180
+        );
+
+        // Only the crops with a variant for the region are loaded
+        let mediterranean = load_crop_coefficients_for_region("Mediterranean").unwrap();
+        assert_eq!(mediterranean["winter_wheat"].late_end_kc.days, 240);
+        assert_eq!(mediterranean["corn"].late_end_kc.days, 150);
+        let plains = load_crop_coefficients_for_region("Great Plains").unwrap();
+        assert_eq!(plains["winter_wheat"].late_end_kc.days, 335);
+        assert_eq!(plains["sorghum"].late_end_kc.days, 125);
+        assert!(!plains.contains_key("oats"));
+
+        let antarctica = load_crop_coefficients_for_region("Antarctica").unwrap_err();
+        assert!(antarctica.to_string().contains("Antarctica"));
+    }
+
+    #[test]
+    fn test_depletion_fraction_at() {
+        let cc = corn();
+        assert_eq!(cc.depletion_fraction_at(5.0), None);
+
+        let p = cc.with_depletion_fraction(0.55).depletion_fraction_at(7.0);
+        assert!((p.unwrap() - 0.47).abs() < 0.0001);
+    }
+}
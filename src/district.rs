@@ -0,0 +1,315 @@
+use crate::kc_model::KcModel;
+use crate::season::{simulate_season, SeasonOptions};
+use crate::water_use::{season_water_use, EffectivePrecipitation};
+use crate::weather::WeatherRecord;
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Add;
+
+const SQUARE_METERS_PER_ACRE: f64 = 4046.8564224;
+const CUBIC_METERS_PER_ACRE_FOOT: f64 = 1233.48183754752;
+const MM_PER_INCH: f64 = 25.4;
+
+// Volume of water over an area, given as a depth over the area or as a volume.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaterAmount {
+    pub volume: f64,
+    pub area: f64,
+}
+
+impl WaterAmount {
+    /// Creates a new `WaterAmount` from a depth in mm over an area in acres.
+    pub fn from_depth(depth_mm: f64, acres: f64) -> Self {
+        let area = acres * SQUARE_METERS_PER_ACRE;
+        WaterAmount {
+            volume: depth_mm / 1000.0 * area,
+            area,
+        }
+    }
+
+    /// Returns the depth over the area in mm.
+    pub fn mm(&self) -> f64 {
+        if self.area > 0.0 {
+            self.volume / self.area * 1000.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the depth over the area in inches.
+    pub fn inches(&self) -> f64 {
+        self.mm() / MM_PER_INCH
+    }
+
+    /// Returns the volume in m³.
+    pub fn cubic_meters(&self) -> f64 {
+        self.volume
+    }
+
+    /// Returns the volume in acre-feet.
+    pub fn acre_feet(&self) -> f64 {
+        self.volume / CUBIC_METERS_PER_ACRE_FOOT
+    }
+}
+
+impl Add for WaterAmount {
+    type Output = WaterAmount;
+
+    // Volumes over the same area add up
+    fn add(self, other: WaterAmount) -> WaterAmount {
+        WaterAmount {
+            volume: self.volume + other.volume,
+            area: self.area.max(other.area),
+        }
+    }
+}
+
+// Crop ET (ETc) and net irrigation demand of the district over a period, with depths weighted by the area of each crop
+// over the total area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DistrictTotals {
+    pub etc: WaterAmount,
+    pub net_irrigation: WaterAmount,
+}
+
+impl Add for DistrictTotals {
+    type Output = DistrictTotals;
+
+    fn add(self, other: DistrictTotals) -> DistrictTotals {
+        DistrictTotals {
+            etc: self.etc + other.etc,
+            net_irrigation: self.net_irrigation + other.net_irrigation,
+        }
+    }
+}
+
+// Daily water use of a district with a mix of crops, counting each crop from planting to the end of its season.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistrictWaterUse {
+    pub total_acres: f64,
+    pub days: Vec<(NaiveDate, DistrictTotals)>,
+}
+
+impl DistrictWaterUse {
+    /// Returns the totals of each calendar month as `((year, month), totals)` in date order.
+    pub fn monthly(&self) -> Vec<((i32, u32), DistrictTotals)> {
+        let mut months: BTreeMap<(i32, u32), DistrictTotals> = BTreeMap::new();
+        for (date, totals) in &self.days {
+            let key = (date.year(), date.month());
+            let total = months.get(&key).map_or(*totals, |month| *month + *totals);
+            months.insert(key, total);
+        }
+        months.into_iter().collect()
+    }
+
+    /// Returns the totals of the season.
+    pub fn season(&self) -> DistrictTotals {
+        let empty = WaterAmount::from_depth(0.0, self.total_acres);
+        self.days.iter().fold(
+            DistrictTotals {
+                etc: empty,
+                net_irrigation: empty,
+            },
+            |season, (_, totals)| season + *totals,
+        )
+    }
+}
+
+/// Aggregates the crop ET (ETc) and net irrigation demand of a district with a mix of crops. Each crop is simulated once;
+/// its net irrigation demand is the monthly net irrigation requirement of the simulation (see `seasonal_water_use`), spread
+/// over the days of the month in proportion to the ETc.
+///
+/// # Parameters
+///
+/// - `crops`: The `KcModel` of each crop by crop name, such as the crop coefficients of `load_crop_coefficients` or the
+///   land covers of `load_land_covers`.
+/// - `acreage`: The area of each crop in acres, by crop name.
+/// - `weather`: A slice of `WeatherRecord` in date order with the ETo and precipitation in mm.
+/// - `options`: The `SeasonOptions` of the season simulation of each crop.
+/// - `method`: The `EffectivePrecipitation` method.
+///
+/// # Returns
+///
+/// A `Result` with the `DistrictWaterUse`, or an error if a crop of the acreage is not in the crop coefficients.
+pub fn district_water_use<M: KcModel>(
+    crops: &HashMap<String, M>,
+    acreage: &HashMap<String, f64>,
+    weather: &[WeatherRecord],
+    options: &SeasonOptions,
+    method: EffectivePrecipitation,
+) -> Result<DistrictWaterUse, Box<dyn std::error::Error>> {
+    let total_acres: f64 = acreage.values().sum();
+    let eto_by_date: HashMap<NaiveDate, f32> = weather
+        .iter()
+        .filter_map(|r| r.eto.map(|eto| (r.date, eto)))
+        .collect();
+
+    let mut volumes: BTreeMap<NaiveDate, (f64, f64)> = BTreeMap::new();
+    for (crop_name, acres) in acreage {
+        let cc = crops.get(crop_name).ok_or(format!(
+            "Crop {} not found in the crop coefficients.",
+            crop_name
+        ))?;
+        let area = acres * SQUARE_METERS_PER_ACRE;
+
+        // Fraction of the ETc of each month that is net irrigation demand
+        let season = simulate_season(cc, weather, options);
+        let summary = season_water_use(cc, &season, weather, method);
+        let demand_fractions: HashMap<(i32, u32), f32> = summary
+            .months
+            .iter()
+            .map(|m| {
+                let fraction = if m.etc > 0.0 {
+                    m.net_irrigation_requirement / m.etc
+                } else {
+                    0.0
+                };
+                ((m.year, m.month), fraction)
+            })
+            .collect();
+
+        for day in season.days.iter().filter(|d| d.crop_active) {
+            let Some(eto) = eto_by_date.get(&day.date) else {
+                continue;
+            };
+            let etc = day.kc * eto;
+            let fraction = demand_fractions
+                .get(&(day.date.year(), day.date.month()))
+                .copied()
+                .unwrap_or(0.0);
+
+            let volume = volumes.entry(day.date).or_insert((0.0, 0.0));
+            volume.0 += etc as f64 / 1000.0 * area;
+            volume.1 += (etc * fraction) as f64 / 1000.0 * area;
+        }
+    }
+
+    let total_area = total_acres * SQUARE_METERS_PER_ACRE;
+    let days = volumes
+        .into_iter()
+        .map(|(date, (etc, net_irrigation))| {
+            (
+                date,
+                DistrictTotals {
+                    etc: WaterAmount {
+                        volume: etc,
+                        area: total_area,
+                    },
+                    net_irrigation: WaterAmount {
+                        volume: net_irrigation,
+                        area: total_area,
+                    },
+                },
+            )
+        })
+        .collect();
+
+    Ok(DistrictWaterUse { total_acres, days })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kcc_gs::CropCoefficientsGs;
+
+    fn crops() -> HashMap<String, CropCoefficientsGs> {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+        let mut crops = HashMap::new();
+        crops.insert(
+            "corn".to_string(),
+            CropCoefficientsGs::new(
+                "corn".to_string(),
+                (20, 1.0),
+                (50, 1.0),
+                (100, 1.0),
+                (120, 1.0),
+                planting_date,
+                2.0,
+            ),
+        );
+        crops.insert(
+            "soybeans".to_string(),
+            CropCoefficientsGs::new(
+                "soybeans".to_string(),
+                (20, 0.5),
+                (50, 0.5),
+                (100, 0.5),
+                (120, 0.5),
+                planting_date,
+                0.8,
+            ),
+        );
+        crops
+    }
+
+    // 5 mm/day of ETo without rain from April through September
+    fn weather() -> Vec<WeatherRecord> {
+        let start = NaiveDate::from_ymd_opt(2023, 4, 1).unwrap();
+        (0..183)
+            .map(|day| {
+                WeatherRecord::new(start + chrono::Duration::days(day), 28.0, 15.0)
+                    .with_eto(5.0)
+                    .with_precipitation(0.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_water_amount_units() {
+        // One acre-foot is 12 inches over an acre
+        let amount = WaterAmount::from_depth(304.8, 1.0);
+        assert!((amount.acre_feet() - 1.0).abs() < 1e-9);
+        assert!((amount.inches() - 12.0).abs() < 1e-9);
+        assert!((amount.cubic_meters() - 1233.4818).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_district_water_use_is_area_weighted() {
+        let mut acreage = HashMap::new();
+        acreage.insert("corn".to_string(), 300.0);
+        acreage.insert("soybeans".to_string(), 100.0);
+
+        let district = district_water_use(
+            &crops(),
+            &acreage,
+            &weather(),
+            &SeasonOptions::default(),
+            EffectivePrecipitation::UsdaScs,
+        )
+        .unwrap();
+
+        // 3/4 of the area uses 5 mm/day and 1/4 uses 2.5 mm/day
+        assert_eq!(district.total_acres, 400.0);
+        assert_eq!(district.days.len(), 121);
+        let (date, day) = district.days[0];
+        assert_eq!(date, NaiveDate::from_ymd_opt(2023, 5, 1).unwrap());
+        assert!((day.etc.mm() - 4.375).abs() < 0.0001);
+        assert!((day.net_irrigation.mm() - 4.375).abs() < 0.0001);
+
+        let may = district.monthly()[0].1;
+        assert!((may.etc.mm() - 31.0 * 4.375).abs() < 0.001);
+
+        let season = district.season();
+        assert!((season.etc.mm() - 121.0 * 4.375).abs() < 0.01);
+        let expected_acre_feet = 121.0 * 4.375 / 304.8 * 400.0;
+        assert!((season.etc.acre_feet() - expected_acre_feet).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_unknown_crop_is_an_error() {
+        let mut acreage = HashMap::new();
+        acreage.insert("sorghum".to_string(), 10.0);
+
+        let result = district_water_use(
+            &crops(),
+            &acreage,
+            &weather(),
+            &SeasonOptions::default(),
+            EffectivePrecipitation::UsdaScs,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Crop sorghum not found in the crop coefficients."
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::kc_gdd::adjust_kc;
+use crate::kcc_gs::CropCoefficientsGs;
+
+// Multiplier on the effective ground cover of the upper limit of Kd (FAO-56 Eq. 98), 1.5 to 2.0 depending on the canopy
+// density and thickness.
+const ML: f32 = 1.5;
+
+// Minimum Kc of bare soil for annual crops under typical conditions (FAO-56 Eq. 97).
+pub const KC_MIN: f32 = 0.15;
+
+// Ground cover of a stand, as the effective fraction of the ground covered or shaded by vegetation near solar noon, the leaf
+// area index (LAI), or the plant density in plants per m² with the canopy area of a plant in m².
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GroundCover {
+    Fraction(f32),
+    LeafAreaIndex(f32),
+    PlantDensity {
+        plants_per_m2: f32,
+        canopy_area: f32,
+    },
+}
+
+impl GroundCover {
+    /// Returns the effective fraction of ground cover (0 - 1).
+    pub fn effective_fraction(&self) -> f32 {
+        match self {
+            GroundCover::Fraction(fc) => fc.clamp(0.0, 1.0),
+            // Fraction of the ground shaded by the leaves for a light extinction coefficient of 0.7
+            GroundCover::LeafAreaIndex(lai) => 1.0 - (-0.7 * lai.max(0.0)).exp(),
+            GroundCover::PlantDensity {
+                plants_per_m2,
+                canopy_area,
+            } => (plants_per_m2 * canopy_area).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Calculates the density coefficient Kd (FAO-56 Eq. 98, Eq. 101 from the LAI).
+    ///
+    /// # Parameters
+    ///
+    /// - `crop_height`: The mean crop height in meters.
+    ///
+    /// # Returns
+    ///
+    /// The density coefficient, between 0 and 1.
+    pub fn density_coefficient(&self, crop_height: f32) -> f32 {
+        match self {
+            GroundCover::LeafAreaIndex(lai) => 1.0 - (-0.7 * lai.max(0.0)).exp(),
+            _ => {
+                let fc = self.effective_fraction();
+                1.0_f32
+                    .min(ML * fc)
+                    .min(fc.powf(1.0 / (1.0 + crop_height.max(0.0))))
+            }
+        }
+    }
+}
+
+/// Calculates the effective fraction of ground cover from the fraction observed from overhead and the solar elevation at noon
+/// (FAO-56 Eq. 102), for canopies that shade more ground than they cover such as tree rows.
+///
+/// # Parameters
+///
+/// - `fc`: The fraction of ground covered by the canopy seen from directly overhead.
+/// - `solar_elevation`: The angle of the sun above the horizon at solar noon in degrees.
+///
+/// # Returns
+///
+/// The effective fraction of ground cover, no more than 1.
+pub fn effective_ground_cover(fc: f32, solar_elevation: f32) -> f32 {
+    let sin = solar_elevation.to_radians().sin();
+    if sin <= 0.0 {
+        return fc.clamp(0.0, 1.0);
+    }
+    (fc / sin).clamp(0.0, 1.0)
+}
+
+/// Calculates the basal crop coefficient of full cover vegetation (FAO-56 Eq. 99) from its height, adjusted for the climate.
+///
+/// # Parameters
+///
+/// - `crop_height`: The mean crop height in meters.
+/// - `wind_speed`: The mean wind speed at 2 m in m/s.
+/// - `rh_min`: The mean minimum relative humidity in %.
+///
+/// # Returns
+///
+/// Kcb_full, the basal crop coefficient at full ground cover.
+pub fn kcb_full(crop_height: f32, wind_speed: f32, rh_min: f32) -> f32 {
+    let kcb_height = (1.0 + 0.1 * crop_height).min(1.2);
+    adjust_kc(kcb_height, wind_speed, rh_min, crop_height)
+}
+
+/// Calculates the crop coefficient of a sparse stand from its ground cover (FAO-56 Eq. 97), Kc = Kc_min + Kd (Kc_full - Kc_min).
+/// Applies to Kcb with Kcb_full and to the single Kc with the Kc of a full stand.
+///
+/// # Parameters
+///
+/// - `cover`: The `GroundCover` of the stand.
+/// - `crop_height`: The mean crop height in meters.
+/// - `kc_min`: The minimum Kc of bare soil, `KC_MIN` for typical conditions.
+/// - `kc_full`: The Kc or Kcb of the crop at full ground cover.
+///
+/// # Returns
+///
+/// The crop coefficient of the stand.
+pub fn kc_from_ground_cover(
+    cover: &GroundCover,
+    crop_height: f32,
+    kc_min: f32,
+    kc_full: f32,
+) -> f32 {
+    if kc_full <= kc_min {
+        return kc_full;
+    }
+    kc_min + cover.density_coefficient(crop_height) * (kc_full - kc_min)
+}
+
+impl CropCoefficientsGs {
+    /// Returns the crop coefficients of a sparse stand (FAO-56 Eq. 97), with Kc_mid and Kc_end reduced by the density
+    /// coefficient of the ground cover at the crop height. The end of the development stage ramps up to the reduced Kc_mid.
+    ///
+    /// # Parameters
+    ///
+    /// - `cover`: The `GroundCover` of the stand at mid-season.
+    /// - `kc_min`: The minimum Kc of bare soil, `KC_MIN` for typical conditions.
+    ///
+    /// # Returns
+    ///
+    /// The `CropCoefficientsGs` of the stand.
+    pub fn with_ground_cover(mut self, cover: &GroundCover, kc_min: f32) -> CropCoefficientsGs {
+        let crop_height = self.crop_height as f32;
+        let mid_kc = kc_from_ground_cover(cover, crop_height, kc_min, self.mid_end_kc.kc);
+
+        self.development_end_kc.kc += mid_kc - self.mid_end_kc.kc;
+        self.mid_end_kc.kc = mid_kc;
+        self.late_end_kc.kc = kc_from_ground_cover(cover, crop_height, kc_min, self.late_end_kc.kc);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_density_coefficient() {
+        // Tall dense canopy limited by the height term, sparse canopy by the ML term
+        let cover = GroundCover::Fraction(0.8);
+        assert!((cover.density_coefficient(3.0) - 0.8_f32.powf(0.25)).abs() < 0.0001);
+        let cover = GroundCover::Fraction(0.1);
+        assert!((cover.density_coefficient(0.5) - 0.15).abs() < 0.0001);
+        assert_eq!(GroundCover::Fraction(1.0).density_coefficient(2.0), 1.0);
+
+        let lai = GroundCover::LeafAreaIndex(3.0);
+        assert!((lai.density_coefficient(1.0) - 0.8775).abs() < 0.0001);
+
+        let density = GroundCover::PlantDensity {
+            plants_per_m2: 0.04,
+            canopy_area: 10.0,
+        };
+        assert!((density.effective_fraction() - 0.4).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_effective_ground_cover() {
+        assert!((effective_ground_cover(0.25, 30.0) - 0.5).abs() < 0.0001);
+        assert_eq!(effective_ground_cover(0.8, 30.0), 1.0);
+    }
+
+    #[test]
+    fn test_kc_from_ground_cover() {
+        // Standard climate leaves the height term of Kcb_full unadjusted
+        assert!((kcb_full(2.0, 2.0, 45.0) - 1.2).abs() < 0.0001);
+        assert!((kcb_full(0.5, 2.0, 45.0) - 1.05).abs() < 0.0001);
+
+        let kcb = kc_from_ground_cover(&GroundCover::Fraction(0.1), 0.5, KC_MIN, 1.05);
+        assert!((kcb - (0.15 + 0.15 * 0.9)).abs() < 0.0001);
+        assert_eq!(
+            kc_from_ground_cover(&GroundCover::Fraction(0.1), 0.5, KC_MIN, 0.1),
+            0.1
+        );
+    }
+
+    #[test]
+    fn test_with_ground_cover() {
+        let cc = CropCoefficientsGs::new(
+            "young_orchard".to_string(),
+            (20, 0.5),
+            (50, 0.9),
+            (100, 0.9),
+            (120, 0.65),
+            NaiveDate::from_ymd_opt(2023, 4, 1).unwrap(),
+            3.0,
+        )
+        .with_ground_cover(&GroundCover::Fraction(0.3), KC_MIN);
+
+        let kd = 0.45;
+        assert!((cc.mid_end_kc.kc - (0.15 + kd * 0.75)).abs() < 0.0001);
+        assert_eq!(cc.development_end_kc.kc, cc.mid_end_kc.kc);
+        assert!((cc.late_end_kc.kc - (0.15 + kd * 0.5)).abs() < 0.0001);
+        assert_eq!(cc.initial_end_kc.kc, 0.5);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::weather::WeatherRecord;
+use chrono::{Datelike, NaiveDate};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+// Thresholds of the growing season. Frost is a day with a minimum temperature at or below `frost_temp` (°C), frosts before
+// day of year `midsummer_day` are spring frosts and frosts after it are fall frosts. The soil is ready for planting when the
+// mean air temperature of the last `soil_temp_days` consecutive days, an estimate of the soil temperature near the surface, reaches
+// `planting_soil_temp` (°C) in the spring after the last spring frost.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowingSeasonOptions {
+    pub frost_temp: f32,
+    pub midsummer_day: u32,
+    pub planting_soil_temp: f32,
+    pub soil_temp_days: usize,
+}
+
+impl Default for GrowingSeasonOptions {
+    // Frost at 0 °C split on July 1, planting when the 5-day mean reaches 10 °C
+    fn default() -> Self {
+        GrowingSeasonOptions {
+            frost_temp: 0.0,
+            midsummer_day: 182,
+            planting_soil_temp: 10.0,
+            soil_temp_days: 5,
+        }
+    }
+}
+
+// Growing season of one year of record, dates are `None` where the year has no frost or the soil does not warm up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrowingSeason {
+    pub year: i32,
+    pub last_spring_frost: Option<NaiveDate>,
+    pub first_fall_frost: Option<NaiveDate>,
+    pub planting_ready: Option<NaiveDate>,
+}
+
+impl GrowingSeason {
+    /// Returns the number of days from the last spring frost to the first fall frost, if both occurred.
+    pub fn frost_free_days(&self) -> Option<i64> {
+        match (self.last_spring_frost, self.first_fall_frost) {
+            (Some(spring), Some(fall)) => Some(fall.signed_duration_since(spring).num_days()),
+            _ => None,
+        }
+    }
+}
+
+/// Determines the growing season of each year in a daily temperature record.
+///
+/// # Parameters
+///
+/// - `weather`: A slice of `WeatherRecord` in date order with the maximum and minimum temperature in °C.
+/// - `options`: The `GrowingSeasonOptions` with the frost and planting thresholds.
+///
+/// # Returns
+///
+/// A `Vec<GrowingSeason>` with one `GrowingSeason` for each year in the record, in order of year.
+pub fn growing_seasons(
+    weather: &[WeatherRecord],
+    options: &GrowingSeasonOptions,
+) -> Vec<GrowingSeason> {
+    let mut years: BTreeMap<i32, Vec<&WeatherRecord>> = BTreeMap::new();
+    for record in weather {
+        years.entry(record.date.year()).or_default().push(record);
+    }
+
+    years
+        .into_iter()
+        .map(|(year, records)| growing_season(year, &records, options))
+        .collect()
+}
+
+// Growing season of the records of one year.
+fn growing_season(
+    year: i32,
+    records: &[&WeatherRecord],
+    options: &GrowingSeasonOptions,
+) -> GrowingSeason {
+    let is_spring = |r: &&&WeatherRecord| r.date.ordinal() < options.midsummer_day;
+    let is_frost = |r: &&&WeatherRecord| r.min_temp <= options.frost_temp;
+
+    let last_spring_frost = records
+        .iter()
+        .filter(is_spring)
+        .rfind(is_frost)
+        .map(|r| r.date);
+    let first_fall_frost = records
+        .iter()
+        .filter(|r| !is_spring(r))
+        .find(is_frost)
+        .map(|r| r.date);
+
+    let window = options.soil_temp_days.max(1);
+    // Only windows of consecutive days after the last spring frost, a gap in the record breaks the running mean and an early
+    // thaw before a frost does not make the soil ready
+    let planting_ready = records
+        .windows(window)
+        .filter(|days| last_spring_frost.is_none_or(|frost| days[0].date > frost))
+        .filter(|days| {
+            days.windows(2)
+                .all(|pair| pair[0].date.succ_opt() == Some(pair[1].date))
+        })
+        .filter(|days| is_spring(&&days[window - 1]))
+        .find(|days| {
+            let mean: f32 = days
+                .iter()
+                .map(|r| (r.max_temp + r.min_temp) / 2.0)
+                .sum::<f32>()
+                / window as f32;
+            mean >= options.planting_soil_temp
+        })
+        .map(|days| days[window - 1].date);
+
+    GrowingSeason {
+        year,
+        last_spring_frost,
+        first_fall_frost,
+        planting_ready,
+    }
+}
+
+// Growing seasons of multiple years of record, giving the frost dates and season length at a risk level. Dates are days of
+// the year, where a year without a frost counts as the safest outcome and a year that never warms up as the worst.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrowingSeasonClimatology {
+    pub seasons: Vec<GrowingSeason>,
+}
+
+impl GrowingSeasonClimatology {
+    /// Creates a `GrowingSeasonClimatology` from a daily temperature record of one or more years.
+    pub fn from_weather(weather: &[WeatherRecord], options: &GrowingSeasonOptions) -> Self {
+        GrowingSeasonClimatology {
+            seasons: growing_seasons(weather, options),
+        }
+    }
+
+    /// Returns the day of the year after which a spring frost occurs in at most `probability` (0 - 1) of the years, or `None`
+    /// if that many years have no spring frost.
+    pub fn last_spring_frost(&self, probability: f32) -> Option<u32> {
+        let days = self.values(|s| s.last_spring_frost.map(|d| d.ordinal()));
+        value_at_risk(days, probability, true, false)
+    }
+
+    /// Returns the day of the year before which a fall frost occurs in at most `probability` (0 - 1) of the years, or `None`
+    /// if that many years have no fall frost.
+    pub fn first_fall_frost(&self, probability: f32) -> Option<u32> {
+        let days = self.values(|s| s.first_fall_frost.map(|d| d.ordinal()));
+        value_at_risk(days, probability, false, false)
+    }
+
+    /// Returns the frost-free season length in days that is not reached in at most `probability` (0 - 1) of the years, where
+    /// years without spring or fall frost are not shorter than any other year.
+    pub fn frost_free_days(&self, probability: f32) -> Option<i64> {
+        let lengths = self.values(|s| s.frost_free_days());
+        value_at_risk(lengths, probability, false, false)
+    }
+
+    /// Returns the day of the year after which the soil is still too cold to plant in at most `probability` (0 - 1) of the
+    /// years, or `None` if that many years never reach the planting soil temperature.
+    pub fn planting_ready(&self, probability: f32) -> Option<u32> {
+        let days = self.values(|s| s.planting_ready.map(|d| d.ordinal()));
+        value_at_risk(days, probability, true, true)
+    }
+
+    fn values<T>(&self, value: impl Fn(&GrowingSeason) -> Option<T>) -> Vec<Option<T>> {
+        self.seasons.iter().map(value).collect()
+    }
+}
+
+// Value of the years at a risk level, with the years sorted from the worst outcome, which is the latest value if `late` and
+// the earliest otherwise, and years without a value first if `missing_is_worst` and last otherwise.
+fn value_at_risk<T: Ord + Copy>(
+    mut values: Vec<Option<T>>,
+    probability: f32,
+    late: bool,
+    missing_is_worst: bool,
+) -> Option<T> {
+    values.sort_by(|a, b| match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) if missing_is_worst => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) if missing_is_worst => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) if late => b.cmp(a),
+        (Some(a), Some(b)) => a.cmp(b),
+    });
+    *values.get(risk_index(probability, values.len())?)?
+}
+
+// Index of the sorted years at a probability, rounding toward the safe side.
+fn risk_index(probability: f32, years: usize) -> Option<usize> {
+    if years == 0 {
+        return None;
+    }
+    let index = (probability.clamp(0.0, 1.0) * years as f32).floor() as usize;
+    Some(index.min(years - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A year of weather with a mean of -10 °C in mid-January and 25 °C in mid-July, frost only on the given days of the year
+    fn year(year: i32, frost_days: &[u32]) -> Vec<WeatherRecord> {
+        let days = NaiveDate::from_ymd_opt(year, 12, 31).unwrap().ordinal();
+        (1..=days)
+            .map(|day| {
+                let date = NaiveDate::from_yo_opt(year, day).unwrap();
+                let seasonal =
+                    7.5 - 17.5 * (2.0 * std::f32::consts::PI * (day as f32 - 15.0) / 365.0).cos();
+                let min_temp = if frost_days.contains(&day) {
+                    -1.0
+                } else {
+                    (seasonal - 5.0).max(1.0)
+                };
+                WeatherRecord::new(date, seasonal + 5.0, min_temp)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_growing_season_frost_dates() {
+        let weather = year(2023, &[90, 120, 280, 300]);
+        let seasons = growing_seasons(&weather, &GrowingSeasonOptions::default());
+
+        assert_eq!(seasons.len(), 1);
+        let season = seasons[0];
+        assert_eq!(season.year, 2023);
+        assert_eq!(season.last_spring_frost, NaiveDate::from_yo_opt(2023, 120));
+        assert_eq!(season.first_fall_frost, NaiveDate::from_yo_opt(2023, 280));
+        assert_eq!(season.frost_free_days(), Some(160));
+    }
+
+    #[test]
+    fn test_planting_ready_from_mean_temperature() {
+        let weather = year(2023, &[]);
+        let season = growing_seasons(&weather, &GrowingSeasonOptions::default())[0];
+        let ready = season.planting_ready.unwrap();
+
+        // The 5 days up to the ready date average at least 10 °C and the day before do not
+        let mean = |end: u32| {
+            weather[(end - 5) as usize..end as usize]
+                .iter()
+                .map(|r| (r.max_temp + r.min_temp) / 2.0)
+                .sum::<f32>()
+                / 5.0
+        };
+        assert!(mean(ready.ordinal()) >= 10.0);
+        assert!(mean(ready.ordinal() - 1) < 10.0);
+        assert_eq!(season.last_spring_frost, None);
+    }
+
+    #[test]
+    fn test_planting_ready_needs_consecutive_days() {
+        let weather = year(2023, &[]);
+        let options = GrowingSeasonOptions::default();
+        let ready = growing_seasons(&weather, &options)[0]
+            .planting_ready
+            .unwrap()
+            .ordinal();
+
+        // Without the 6 days around the ready date, the first 5 consecutive days start after the gap
+        let gap: Vec<WeatherRecord> = weather
+            .into_iter()
+            .filter(|r| !(ready - 3..=ready + 2).contains(&r.date.ordinal()))
+            .collect();
+        let season = growing_seasons(&gap, &options)[0];
+        assert_eq!(season.planting_ready.unwrap().ordinal(), ready + 7);
+    }
+
+    #[test]
+    fn test_planting_ready_after_last_spring_frost() {
+        // A warm spell in March followed by a frost on day 120
+        let weather: Vec<WeatherRecord> = year(2023, &[120])
+            .into_iter()
+            .map(|r| {
+                if (60..=70).contains(&r.date.ordinal()) {
+                    WeatherRecord::new(r.date, 20.0, 10.0)
+                } else {
+                    r
+                }
+            })
+            .collect();
+        let season = growing_seasons(&weather, &GrowingSeasonOptions::default())[0];
+
+        // The first 5 days after the frost average above 10 °C
+        assert_eq!(season.last_spring_frost, NaiveDate::from_yo_opt(2023, 120));
+        assert_eq!(season.planting_ready, NaiveDate::from_yo_opt(2023, 125));
+    }
+
+    #[test]
+    fn test_frost_risk_across_years() {
+        let mut weather = Vec::new();
+        for (i, spring) in [100, 105, 110, 115, 120, 125, 130, 135, 140, 145]
+            .iter()
+            .enumerate()
+        {
+            weather.extend(year(2010 + i as i32, &[*spring, 270 + 2 * i as u32]));
+        }
+        let climatology =
+            GrowingSeasonClimatology::from_weather(&weather, &GrowingSeasonOptions::default());
+
+        assert_eq!(climatology.seasons.len(), 10);
+        assert_eq!(climatology.last_spring_frost(0.0), Some(145));
+        assert_eq!(climatology.last_spring_frost(0.1), Some(140));
+        assert_eq!(climatology.last_spring_frost(0.5), Some(120));
+        assert_eq!(climatology.first_fall_frost(0.1), Some(272));
+        assert_eq!(climatology.first_fall_frost(0.5), Some(280));
+        assert_eq!(climatology.frost_free_days(0.0), Some(143));
+    }
+
+    #[test]
+    fn test_years_without_frost_are_safest() {
+        let mut weather = year(2020, &[]);
+        weather.extend(year(2021, &[110, 290]));
+        let climatology =
+            GrowingSeasonClimatology::from_weather(&weather, &GrowingSeasonOptions::default());
+
+        assert_eq!(climatology.last_spring_frost(0.0), Some(110));
+        assert_eq!(climatology.last_spring_frost(0.5), None);
+        assert_eq!(climatology.first_fall_frost(0.0), Some(290));
+        assert_eq!(climatology.first_fall_frost(0.5), None);
+        assert_eq!(climatology.frost_free_days(0.0), Some(180));
+    }
+}
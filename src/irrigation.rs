@@ -0,0 +1,316 @@
+use crate::kc_model::KcModel;
+use crate::weather::WeatherRecord;
+use chrono::NaiveDate;
+
+const MM_PER_INCH: f32 = 25.4;
+
+// Irrigation system limits, depths are in mm. The capacity is the gross depth the system can apply per day and the
+// efficiency the fraction of the gross depth that reaches the root zone.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IrrigationSystem {
+    pub capacity: f32,
+    pub efficiency: f32,
+    pub min_application: f32,
+}
+
+impl IrrigationSystem {
+    /// Creates a new `IrrigationSystem` with the capacity in mm/day, the application efficiency (0 - 1) and the minimum net
+    /// application depth in mm. Panics if the capacity or efficiency is not above 0.
+    pub fn new(capacity: f32, efficiency: f32, min_application: f32) -> Self {
+        if capacity <= 0.0 {
+            panic!("System capacity must be above 0.");
+        }
+        if efficiency <= 0.0 || efficiency > 1.0 {
+            panic!("Application efficiency must be above 0 and no more than 1.");
+        }
+
+        IrrigationSystem {
+            capacity,
+            efficiency,
+            min_application,
+        }
+    }
+
+    /// Creates a new `IrrigationSystem` with the capacity in in/day and the minimum net application depth in inches.
+    pub fn from_inches(capacity: f32, efficiency: f32, min_application: f32) -> Self {
+        IrrigationSystem::new(
+            capacity * MM_PER_INCH,
+            efficiency,
+            min_application * MM_PER_INCH,
+        )
+    }
+
+    // Largest net depth the system can apply in a day
+    fn max_net_application(&self) -> f32 {
+        self.capacity * self.efficiency
+    }
+}
+
+/// Calculates the total available water (TAW) of the root zone from the available water capacity of the soil.
+///
+/// # Parameters
+///
+/// - `available_water_capacity`: The water held between field capacity and wilting point in mm per m of soil.
+/// - `root_depth`: The depth of the root zone in meters.
+///
+/// # Returns
+///
+/// The total available water in mm.
+pub fn total_available_water(available_water_capacity: f32, root_depth: f32) -> f32 {
+    available_water_capacity * root_depth
+}
+
+// Inputs of an irrigation schedule. Depths are in mm, the depletion is the root zone depletion below field capacity at the
+// start of the forecast and irrigation is triggered when the depletion would exceed the management allowed depletion
+// (MAD, fraction of TAW). No irrigation is scheduled after the cutoff date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScheduleOptions {
+    pub total_available_water: f32,
+    pub management_allowed_depletion: f32,
+    pub initial_depletion: f32,
+    pub system: IrrigationSystem,
+    pub cutoff_date: Option<NaiveDate>,
+}
+
+impl ScheduleOptions {
+    /// Returns the depletion in mm that triggers irrigation, the readily available water (RAW) at the MAD.
+    pub fn trigger_depletion(&self) -> f32 {
+        self.management_allowed_depletion * self.total_available_water
+    }
+}
+
+// Irrigation on a date, with the net depth reaching the root zone and the gross depth applied in mm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IrrigationEvent {
+    pub date: NaiveDate,
+    pub net_depth: f32,
+    pub gross_depth: f32,
+}
+
+// Root zone water balance of a day in mm, with the depletion at the end of the day and the water stress coefficient Ks
+// that reduces the crop ET (ETc) to the actual ET when the depletion exceeds the MAD.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepletionDay {
+    pub date: NaiveDate,
+    pub kc: f32,
+    pub etc: f32,
+    pub ks: f32,
+    pub actual_et: f32,
+    pub precipitation: f32,
+    pub irrigation: f32,
+    pub deep_percolation: f32,
+    pub depletion: f32,
+}
+
+// Irrigation events and the daily depletion trace of a forecast.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrrigationSchedule {
+    pub events: Vec<IrrigationEvent>,
+    pub trace: Vec<DepletionDay>,
+}
+
+impl IrrigationSchedule {
+    /// Returns the first irrigation of the schedule, if any is due.
+    pub fn next_irrigation(&self) -> Option<&IrrigationEvent> {
+        self.events.first()
+    }
+
+    /// Returns the total net irrigation depth of the schedule in mm.
+    pub fn total_net_depth(&self) -> f32 {
+        self.events.iter().map(|e| e.net_depth).sum()
+    }
+}
+
+/// Schedules irrigation over a forecast with a daily root zone water balance (FAO-56 Eq. 85), irrigating to field capacity
+/// on the days the depletion would otherwise exceed the MAD.
+///
+/// # Parameters
+///
+/// - `model`: The `KcModel` of the crop, adjusted each day with the wind speed and humidity of the forecast.
+/// - `forecast`: A slice of `WeatherRecord` in date order with the ETo and precipitation in mm, days before planting, after the
+///   end of the season (if the model has one) or without ETo are skipped.
+/// - `options`: The `ScheduleOptions` with the soil water, MAD, irrigation system and cutoff date.
+///
+/// # Returns
+///
+/// An `IrrigationSchedule` with the irrigation events and the depletion trace.
+pub fn schedule_irrigation(
+    model: &dyn KcModel,
+    forecast: &[WeatherRecord],
+    options: &ScheduleOptions,
+) -> IrrigationSchedule {
+    let taw = options.total_available_water;
+    let raw = options.trigger_depletion();
+    let planting_date = model.planting_date();
+    let season_end = model.season_end();
+
+    let mut depletion = options.initial_depletion.clamp(0.0, taw);
+    let mut events = Vec::new();
+    let mut trace = Vec::new();
+
+    for record in forecast
+        .iter()
+        .filter(|r| r.date >= planting_date && season_end.is_none_or(|end| r.date <= end))
+    {
+        let Some(eto) = record.eto else { continue };
+        let kc = model.coefficient_from_date(
+            record.date,
+            record.wind_speed_2m(),
+            record.min_relative_humidity(),
+            None,
+        );
+        let etc = kc * eto;
+        let precipitation = record.precipitation.unwrap_or(0.0);
+
+        // Water stress from the depletion at the start of the day (FAO-56 Eq. 84)
+        let ks = water_stress_coefficient(depletion, taw, raw);
+        let actual_et = ks * etc;
+
+        let projected = depletion - precipitation + actual_et;
+        let irrigation_allowed = options
+            .cutoff_date
+            .is_none_or(|cutoff| record.date <= cutoff);
+        let mut irrigation = 0.0;
+        if irrigation_allowed && projected > raw {
+            let net_depth = projected.min(options.system.max_net_application());
+            if net_depth >= options.system.min_application {
+                irrigation = net_depth;
+                events.push(IrrigationEvent {
+                    date: record.date,
+                    net_depth,
+                    gross_depth: net_depth / options.system.efficiency,
+                });
+            }
+        }
+
+        let balance = projected - irrigation;
+        let deep_percolation = (-balance).max(0.0);
+        depletion = balance.clamp(0.0, taw);
+
+        trace.push(DepletionDay {
+            date: record.date,
+            kc,
+            etc,
+            ks,
+            actual_et,
+            precipitation,
+            irrigation,
+            deep_percolation,
+            depletion,
+        });
+    }
+
+    IrrigationSchedule { events, trace }
+}
+
+/// Calculates the water stress coefficient Ks from the root zone depletion (FAO-56 Eq. 84).
+///
+/// # Parameters
+///
+/// - `depletion`: The root zone depletion in mm.
+/// - `taw`: The total available water in mm.
+/// - `raw`: The readily available water in mm.
+///
+/// # Returns
+///
+/// The water stress coefficient, 1 while the depletion is no more than RAW and 0 at TAW.
+pub fn water_stress_coefficient(depletion: f32, taw: f32, raw: f32) -> f32 {
+    if depletion <= raw || taw <= raw {
+        1.0
+    } else {
+        ((taw - depletion) / (taw - raw)).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::corn;
+
+    // Dry mid-season forecast with 6 mm/day of ETo, ETc is 7.2 mm/day
+    fn forecast(days: i64) -> Vec<WeatherRecord> {
+        let start = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        (0..days)
+            .map(|day| {
+                WeatherRecord::new(start + chrono::Duration::days(day), 30.0, 18.0)
+                    .with_eto(6.0)
+                    .with_precipitation(0.0)
+            })
+            .collect()
+    }
+
+    fn options() -> ScheduleOptions {
+        ScheduleOptions {
+            total_available_water: total_available_water(150.0, 1.0),
+            management_allowed_depletion: 0.5,
+            initial_depletion: 40.0,
+            system: IrrigationSystem::new(30.0, 0.85, 10.0),
+            cutoff_date: None,
+        }
+    }
+
+    #[test]
+    fn test_next_irrigation_refills_root_zone() {
+        let options = ScheduleOptions {
+            system: IrrigationSystem::new(100.0, 0.85, 10.0),
+            ..options()
+        };
+        let schedule = schedule_irrigation(&corn(), &forecast(10), &options);
+
+        // 40 mm depletion would exceed the 75 mm trigger on the fifth day
+        let next = schedule.next_irrigation().expect("Expected an irrigation");
+        assert_eq!(next.date, NaiveDate::from_ymd_opt(2023, 7, 5).unwrap());
+        assert!((next.net_depth - 76.0).abs() < 0.01);
+        assert!((next.gross_depth - 89.41).abs() < 0.01);
+        assert_eq!(schedule.trace[4].depletion, 0.0);
+        assert_eq!(schedule.events.len(), 1);
+    }
+
+    #[test]
+    fn test_system_capacity_limits_application() {
+        let schedule = schedule_irrigation(&corn(), &forecast(10), &options());
+        let next = schedule.next_irrigation().unwrap();
+
+        // The 30 mm/day system applies at most 25.5 mm net and irrigates again on the following days
+        assert!((next.net_depth - 25.5).abs() < 0.01);
+        assert!((next.gross_depth - 30.0).abs() < 0.01);
+        assert!(schedule.events.len() > 1);
+        assert!(schedule
+            .trace
+            .iter()
+            .all(|d| d.ks == 1.0 || d.depletion > 75.0));
+    }
+
+    #[test]
+    fn test_depletion_trace_and_rain() {
+        let mut forecast = forecast(3);
+        forecast[1] = forecast[1].with_precipitation(60.0);
+        let options = ScheduleOptions {
+            system: IrrigationSystem::from_inches(2.0, 0.85, 0.5),
+            ..options()
+        };
+
+        let schedule = schedule_irrigation(&corn(), &forecast, &options);
+
+        assert!(schedule.events.is_empty());
+        assert!((schedule.trace[0].depletion - 47.2).abs() < 0.01);
+        assert_eq!(schedule.trace[1].depletion, 0.0);
+        assert!((schedule.trace[1].deep_percolation - 5.6).abs() < 0.01);
+        assert!((schedule.trace[2].depletion - 7.2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_cutoff_date_and_stress() {
+        let options = ScheduleOptions {
+            cutoff_date: NaiveDate::from_ymd_opt(2023, 6, 30),
+            ..options()
+        };
+        let schedule = schedule_irrigation(&corn(), &forecast(20), &options);
+
+        assert!(schedule.events.is_empty());
+        let last = schedule.trace.last().unwrap();
+        assert!(last.ks < 1.0);
+        assert!(last.actual_et < last.etc);
+        assert!(last.depletion < 150.0);
+    }
+}
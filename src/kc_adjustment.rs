@@ -0,0 +1,227 @@
+use std::fmt;
+
+// Valid ranges of the FAO-56 climate adjustment of Kc (Eq. 62), outside of which the inputs are clamped.
+const WIND_SPEED_RANGE: (f32, f32) = (1.0, 6.0);
+const RH_MIN_RANGE: (f32, f32) = (20.0, 80.0);
+const CROP_HEIGHT_RANGE: (f32, f32) = (0.1, 10.0);
+
+// Units of a relative humidity value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HumidityUnit {
+    // Relative humidity in percent (0 - 100)
+    Percent,
+    // Relative humidity as a fraction (0 - 1)
+    Fraction,
+}
+
+// Input to the climate adjustment that was changed or looks suspicious.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdjustmentWarning {
+    // The wind speed was outside 1 - 6 m/s and was clamped to the applied value
+    WindSpeedClamped { value: f32, applied: f32 },
+    // The minimum relative humidity was outside 20 - 80% and was clamped to the applied value
+    RhMinClamped { value: f32, applied: f32 },
+    // The crop height was outside 0.1 - 10 m and was clamped to the applied value
+    CropHeightClamped { value: f32, applied: f32 },
+    // The humidity value does not fit its declared unit, e.g. 0.5 given in percent looks like a fraction
+    AmbiguousHumidityUnit { value: f32, unit: HumidityUnit },
+}
+
+impl fmt::Display for AdjustmentWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdjustmentWarning::WindSpeedClamped { value, applied } => {
+                write!(
+                    f,
+                    "wind speed {} m/s outside 1-6 m/s, using {} m/s",
+                    value, applied
+                )
+            }
+            AdjustmentWarning::RhMinClamped { value, applied } => {
+                write!(f, "RHmin {}% outside 20-80%, using {}%", value, applied)
+            }
+            AdjustmentWarning::CropHeightClamped { value, applied } => {
+                write!(
+                    f,
+                    "crop height {} m outside 0.1-10 m, using {} m",
+                    value, applied
+                )
+            }
+            AdjustmentWarning::AmbiguousHumidityUnit { value, unit } => {
+                write!(
+                    f,
+                    "humidity {} does not look like a value in {:?}",
+                    value, unit
+                )
+            }
+        }
+    }
+}
+
+// Result of the climate adjustment of Kc, with the inputs as applied after clamping and the separate wind and humidity
+// terms, which add up to the total adjustment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KcAdjustment {
+    pub kc: f32,
+    pub adjustment: f32,
+    pub wind_term: f32,
+    pub humidity_term: f32,
+    pub wind_speed: f32,
+    pub rh_min: f32,
+    pub crop_height: f32,
+    pub warnings: Vec<AdjustmentWarning>,
+}
+
+/// Adjusts a tabulated Kc_mid or Kc_end for climates that differ from the sub-humid reference climate (FAO-56 Eq. 62 and 65).
+///
+/// The inputs are clamped to the ranges the equation is valid for (1 ≤ u2 ≤ 6 m/s, 20 ≤ RHmin ≤ 80 % and 0.1 ≤ h ≤ 10 m)
+/// and each clamped input is reported as a warning. The humidity unit must be given explicitly, values that do not fit
+/// the unit (a percentage of 1 or less, or a fraction above 1) are reported instead of converted.
+///
+/// # Parameters
+///
+/// - `kc_original`: The tabulated Kc value to adjust.
+/// - `wind_speed`: The mean wind speed at 2 m height in m/s.
+/// - `rh_min`: The mean minimum relative humidity, in the unit given by `humidity_unit`.
+/// - `humidity_unit`: The `HumidityUnit` of `rh_min`.
+/// - `crop_height`: The mean crop height in meters.
+///
+/// # Returns
+///
+/// A `KcAdjustment` with the adjusted Kc, the applied adjustment terms and inputs, and any warnings.
+pub fn adjust_kc_checked(
+    kc_original: f32,
+    wind_speed: f32,
+    rh_min: f32,
+    humidity_unit: HumidityUnit,
+    crop_height: f32,
+) -> KcAdjustment {
+    let mut warnings = Vec::new();
+
+    let rh_min_percent = match humidity_unit {
+        HumidityUnit::Percent => {
+            if rh_min <= 1.0 {
+                warnings.push(AdjustmentWarning::AmbiguousHumidityUnit {
+                    value: rh_min,
+                    unit: humidity_unit,
+                });
+            }
+            rh_min
+        }
+        HumidityUnit::Fraction => {
+            if rh_min > 1.0 {
+                warnings.push(AdjustmentWarning::AmbiguousHumidityUnit {
+                    value: rh_min,
+                    unit: humidity_unit,
+                });
+            }
+            rh_min * 100.0
+        }
+    };
+
+    let wind_speed_applied = clamp_to_range(wind_speed, WIND_SPEED_RANGE);
+    if wind_speed_applied != wind_speed {
+        warnings.push(AdjustmentWarning::WindSpeedClamped {
+            value: wind_speed,
+            applied: wind_speed_applied,
+        });
+    }
+
+    let rh_min_applied = clamp_to_range(rh_min_percent, RH_MIN_RANGE);
+    if rh_min_applied != rh_min_percent {
+        warnings.push(AdjustmentWarning::RhMinClamped {
+            value: rh_min_percent,
+            applied: rh_min_applied,
+        });
+    }
+
+    let crop_height_applied = clamp_to_range(crop_height, CROP_HEIGHT_RANGE);
+    if crop_height_applied != crop_height {
+        warnings.push(AdjustmentWarning::CropHeightClamped {
+            value: crop_height,
+            applied: crop_height_applied,
+        });
+    }
+
+    let height_factor = (crop_height_applied / 3.0).powf(0.3);
+    let wind_term = 0.04 * (wind_speed_applied - 2.0) * height_factor;
+    let humidity_term = -0.0004 * (rh_min_applied - 45.0) * height_factor;
+    let adjustment = wind_term + humidity_term;
+
+    KcAdjustment {
+        kc: kc_original + adjustment,
+        adjustment,
+        wind_term,
+        humidity_term,
+        wind_speed: wind_speed_applied,
+        rh_min: rh_min_applied,
+        crop_height: crop_height_applied,
+        warnings,
+    }
+}
+
+fn clamp_to_range(value: f32, range: (f32, f32)) -> f32 {
+    value.clamp(range.0, range.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust_kc_checked_within_ranges() {
+        let result = adjust_kc_checked(1.2, 3.0, 30.0, HumidityUnit::Percent, 1.5);
+
+        let height_factor = 0.5_f32.powf(0.3);
+        assert!((result.wind_term - 0.04 * height_factor).abs() < 0.0001);
+        assert!((result.humidity_term - 0.006 * height_factor).abs() < 0.0001);
+        assert!((result.kc - (1.2 + result.adjustment)).abs() < 0.0001);
+        assert!((result.kc - 1.237).abs() < 0.001);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_adjust_kc_checked_clamps_inputs() {
+        let result = adjust_kc_checked(1.2, 8.0, 10.0, HumidityUnit::Percent, 12.0);
+
+        assert_eq!(result.wind_speed, 6.0);
+        assert_eq!(result.rh_min, 20.0);
+        assert_eq!(result.crop_height, 10.0);
+        assert_eq!(
+            result.warnings,
+            vec![
+                AdjustmentWarning::WindSpeedClamped {
+                    value: 8.0,
+                    applied: 6.0
+                },
+                AdjustmentWarning::RhMinClamped {
+                    value: 10.0,
+                    applied: 20.0
+                },
+                AdjustmentWarning::CropHeightClamped {
+                    value: 12.0,
+                    applied: 10.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_adjust_kc_checked_humidity_units() {
+        let fraction = adjust_kc_checked(1.2, 2.0, 0.3, HumidityUnit::Fraction, 2.0);
+        let percent = adjust_kc_checked(1.2, 2.0, 30.0, HumidityUnit::Percent, 2.0);
+        assert_eq!(fraction.kc, percent.kc);
+        assert!(fraction.warnings.is_empty());
+
+        // 0.5% is not converted to 50%, it is flagged and clamped to the lower limit
+        let ambiguous = adjust_kc_checked(1.2, 2.0, 0.5, HumidityUnit::Percent, 2.0);
+        assert_eq!(ambiguous.rh_min, 20.0);
+        assert_eq!(
+            ambiguous.warnings[0],
+            AdjustmentWarning::AmbiguousHumidityUnit {
+                value: 0.5,
+                unit: HumidityUnit::Percent
+            }
+        );
+    }
+}
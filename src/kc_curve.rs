@@ -0,0 +1,591 @@
+use crate::kc_gdd::{adjust_kc, crop_height_profile};
+use crate::kcc_gs::CropCoefficientsGs;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Axis along which the breakpoints of a Kc curve are tabulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveAxis {
+    // Days after planting
+    Days,
+    // Percent (0 - 100) of a season of `season_days` days from planting to the end of the late stage
+    PercentOfSeason { season_days: u16 },
+    // Percent (0 - 100) of the time from planting to effective cover, followed by days after effective cover added to 100,
+    // as used by the Wright (1982) and USBR basal Kc tables
+    EffectiveCover { days_to_cover: u16 },
+    // Fraction (0 - 1) of the growing degree days from planting to maturity
+    NormalizedGdd,
+}
+
+impl CurveAxis {
+    /// Returns the position on the axis for the given days since planting, or `None` for the normalized GDD axis, which
+    /// needs cumulative GDD instead.
+    pub fn position_for_days(&self, days_since_planting: i64) -> Option<f32> {
+        let days = days_since_planting as f32;
+        match self {
+            CurveAxis::Days => Some(days),
+            CurveAxis::PercentOfSeason { season_days } => {
+                Some(100.0 * days / (*season_days).max(1) as f32)
+            }
+            CurveAxis::EffectiveCover { days_to_cover } => {
+                let days_to_cover = (*days_to_cover).max(1) as f32;
+                if days <= days_to_cover {
+                    Some(100.0 * days / days_to_cover)
+                } else {
+                    Some(100.0 + days - days_to_cover)
+                }
+            }
+            CurveAxis::NormalizedGdd => None,
+        }
+    }
+
+    // Days since planting at a position on the axis, the inverse of `position_for_days`.
+    fn days_for_position(&self, position: f32) -> Option<f32> {
+        match self {
+            CurveAxis::Days => Some(position),
+            CurveAxis::PercentOfSeason { season_days } => {
+                Some(position / 100.0 * *season_days as f32)
+            }
+            CurveAxis::EffectiveCover { days_to_cover } => {
+                if position <= 100.0 {
+                    Some(position / 100.0 * *days_to_cover as f32)
+                } else {
+                    Some(*days_to_cover as f32 + position - 100.0)
+                }
+            }
+            CurveAxis::NormalizedGdd => None,
+        }
+    }
+}
+
+// Interpolation between the breakpoints of a Kc curve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interpolation {
+    // Straight lines between breakpoints
+    Linear,
+    // Monotone (shape preserving) cubic spline through the breakpoints, which does not overshoot the tabulated Kc
+    CubicSpline,
+}
+
+// Crop coefficient curve defined by a table of (position, Kc) breakpoints along a `CurveAxis`, for curve shapes that do not
+// fit the four-stage model of `CropCoefficientsGs`. Before the first and after the last breakpoint Kc is constant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KcCurve {
+    pub crop_name: String,
+    pub axis: CurveAxis,
+    pub interpolation: Interpolation,
+    pub planting_date: NaiveDate,
+    pub crop_height: f64,
+    points: Vec<(f32, f32)>,
+    slopes: Vec<f32>,
+}
+
+impl KcCurve {
+    /// Creates a new `KcCurve` from a table of breakpoints.
+    ///
+    /// # Parameters
+    ///
+    /// - `crop_name`: A `String` representing the name of the crop.
+    /// - `axis`: The `CurveAxis` the breakpoint positions are given on.
+    /// - `interpolation`: The `Interpolation` between breakpoints.
+    /// - `points`: A `Vec<(f32, f32)>` of (position, Kc) breakpoints in increasing order of position.
+    /// - `planting_date`: A `NaiveDate` representing the planting date.
+    /// - `crop_height`: The mature crop height in meters, reached at the peak of the curve.
+    ///
+    /// # Returns
+    ///
+    /// A `KcCurve` initialized with the provided parameters. Panics if there are fewer than two breakpoints, if the positions
+    /// are not strictly increasing or if any Kc value is negative or exceeds 2.
+    pub fn new(
+        crop_name: String,
+        axis: CurveAxis,
+        interpolation: Interpolation,
+        points: Vec<(f32, f32)>,
+        planting_date: NaiveDate,
+        crop_height: f64,
+    ) -> KcCurve {
+        if let Err(e) = validate_points(&points) {
+            panic!("{}", e);
+        }
+
+        let slopes = match interpolation {
+            Interpolation::Linear => Vec::new(),
+            Interpolation::CubicSpline => monotone_slopes(&points),
+        };
+
+        KcCurve {
+            crop_name,
+            axis,
+            interpolation,
+            planting_date,
+            crop_height,
+            points,
+            slopes,
+        }
+    }
+
+    /// Returns the (position, Kc) breakpoints of the curve.
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    /// Returns the tabular Kc at a position on the curve axis, before any climate adjustment.
+    pub fn kc_at(&self, position: f32) -> f32 {
+        let first = self.points[0];
+        let last = self.points[self.points.len() - 1];
+        if position <= first.0 {
+            return first.1;
+        }
+        if position >= last.0 {
+            return last.1;
+        }
+
+        let k = self.points.partition_point(|p| p.0 <= position) - 1;
+        let (x0, y0) = self.points[k];
+        let (x1, y1) = self.points[k + 1];
+        let h = x1 - x0;
+        let t = (position - x0) / h;
+        match self.interpolation {
+            Interpolation::Linear => y0 + (y1 - y0) * t,
+            Interpolation::CubicSpline => {
+                let t2 = t * t;
+                let t3 = t2 * t;
+                (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                    + (t3 - 2.0 * t2 + t) * h * self.slopes[k]
+                    + (-2.0 * t3 + 3.0 * t2) * y1
+                    + (t3 - t2) * h * self.slopes[k + 1]
+            }
+        }
+    }
+
+    /// Returns the tabular Kc for a fraction (0 - 1) of the growing degree days from planting to maturity. Panics if the
+    /// curve is not on the normalized GDD axis.
+    pub fn kc_at_gdd_fraction(&self, gdd_fraction: f32) -> f32 {
+        if self.axis != CurveAxis::NormalizedGdd {
+            panic!("Curve is not on the normalized GDD axis.");
+        }
+        self.kc_at(gdd_fraction)
+    }
+
+    /// Returns the tabular Kc for the cumulative GDD of a crop on the normalized GDD axis, given the GDD from planting to
+    /// maturity of the hybrid or variety (see `corn_gdd_to_maturity`). Panics if the curve is not on the normalized GDD axis.
+    pub fn kc_at_gdd(&self, cumulative_gdd: f32, gdd_to_maturity: f32) -> f32 {
+        if gdd_to_maturity <= 0.0 {
+            panic!("GDD to maturity must be positive.");
+        }
+        self.kc_at_gdd_fraction(cumulative_gdd / gdd_to_maturity)
+    }
+
+    /**
+    Calculates the crop coefficient (Kc) on a date by interpolating the breakpoint table, with the same optional climate
+    adjustment as `CropCoefficientsGs::coefficient_from_date`. The adjustment is applied after the first peak of the curve,
+    which takes the place of the mid and late stages.
+
+    # Parameters
+
+    - `date`: A `NaiveDate` for which to calculate Kc.
+    - `wind_speed`: Optional wind speed in m/s (default: 2.0).
+    - `rh_min`: Optional minimum relative humidity in % (default: 45.0).
+    - `crop_height`: Optional measured crop height in meters (default: grows to `crop_height` at the peak of the curve).
+
+    # Returns
+
+    The calculated Kc value as f32. Panics if the curve is on the normalized GDD axis, use `kc_at_gdd_fraction` instead.
+    */
+    pub fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        let days_since_planting = date.signed_duration_since(self.planting_date).num_days();
+        let position = match self.axis.position_for_days(days_since_planting) {
+            Some(position) => position,
+            None => panic!("Normalized GDD curves need cumulative GDD, use kc_at_gdd_fraction."),
+        };
+        let kc = self.kc_at(position);
+
+        let peak = self.peak_position();
+        if position > peak {
+            let peak_days = self.axis.days_for_position(peak).unwrap_or(0.0);
+            let crop_height = crop_height.unwrap_or_else(|| {
+                let progress = if peak_days > 0.0 {
+                    days_since_planting as f32 / peak_days
+                } else {
+                    1.0
+                };
+                crop_height_profile(progress, self.crop_height as f32)
+            });
+            adjust_kc(
+                kc,
+                wind_speed.unwrap_or(2.0),
+                rh_min.unwrap_or(45.0),
+                crop_height,
+            )
+        } else {
+            kc
+        }
+    }
+
+    /// Returns the last day of the curve, or `None` for curves on the normalized GDD axis.
+    pub fn season_end(&self) -> Option<NaiveDate> {
+        let last = self.points[self.points.len() - 1].0;
+        self.axis
+            .days_for_position(last)
+            .map(|days| self.planting_date + chrono::Duration::days(days.ceil() as i64))
+    }
+
+    // Position of the first breakpoint with the highest Kc, the start of the full cover part of the curve.
+    fn peak_position(&self) -> f32 {
+        let mut peak = self.points[0];
+        for &point in &self.points {
+            if point.1 > peak.1 {
+                peak = point;
+            }
+        }
+        peak.0
+    }
+}
+
+impl From<&CropCoefficientsGs> for KcCurve {
+    // The four-stage model as a linear curve over days after planting
+    fn from(cc: &CropCoefficientsGs) -> Self {
+        let mut points = vec![(0.0, cc.initial_end_kc.kc)];
+        for stage in [
+            cc.initial_end_kc,
+            cc.development_end_kc,
+            cc.mid_end_kc,
+            cc.late_end_kc,
+        ] {
+            // Zero length stages would repeat a position
+            if stage.days as f32 > points[points.len() - 1].0 {
+                points.push((stage.days as f32, stage.kc));
+            }
+        }
+
+        KcCurve::new(
+            cc.crop_name.clone(),
+            CurveAxis::Days,
+            Interpolation::Linear,
+            points,
+            cc.planting_date,
+            cc.crop_height,
+        )
+    }
+}
+
+// Kc curve as written in a crop table file
+#[derive(Debug, Serialize, Deserialize)]
+struct Curve {
+    name: String,
+    axis: String, // "days", "percent_of_season", "effective_cover" or "normalized_gdd"
+    interpolation: Interpolation,
+    planting_date: NaiveDate,
+    height_m: f64,
+    season_days: Option<u16>, // Season length for the percent_of_season axis
+    days_to_effective_cover: Option<u16>, // Days from planting to effective cover for the effective_cover axis
+    points: Vec<(f32, f32)>,
+    #[serde(default)]
+    after_cover_points: Vec<(f32, f32)>, // Days after effective cover and Kc for the effective_cover axis
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct KcCurveData {
+    #[serde(default)]
+    curves: HashMap<String, Curve>,
+}
+
+/// Loads the Kc curves from the `[curves]` tables of `fao56.toml`, see `load_kc_curves_from`.
+pub fn load_kc_curves() -> Result<HashMap<String, KcCurve>, Box<dyn std::error::Error>> {
+    load_kc_curves_from("fao56.toml")
+}
+
+/// Loads the Kc curves from the `[curves]` tables of a crop table file.
+///
+/// Each curve has an `axis` of "days", "percent_of_season" (with `season_days`), "effective_cover" (with
+/// `days_to_effective_cover`) or "normalized_gdd", an `interpolation` of "linear" or "cubic_spline" and a list of
+/// `[position, kc]` breakpoints in `points`. Curves on the effective cover axis give the breakpoints before effective
+/// cover in percent of the time to cover in `points` and the breakpoints after cover in days after cover in
+/// `after_cover_points`.
+///
+/// # Parameters
+///
+/// - `path`: The path of the crop table file.
+///
+/// # Returns
+///
+/// A `HashMap<String, KcCurve>` keyed by curve name, or an error if the file cannot be read or a curve is invalid.
+pub fn load_kc_curves_from<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<String, KcCurve>, Box<dyn std::error::Error>> {
+    let toml_str = fs::read_to_string(path)?;
+    parse_kc_curves(&toml_str)
+}
+
+fn parse_kc_curves(toml_str: &str) -> Result<HashMap<String, KcCurve>, Box<dyn std::error::Error>> {
+    let curve_data: KcCurveData = toml::from_str(toml_str)?;
+
+    let mut result = HashMap::new();
+    for curve in curve_data.curves.into_values() {
+        let mut points = curve.points;
+        let axis = match curve.axis.as_str() {
+            "days" => CurveAxis::Days,
+            "percent_of_season" => CurveAxis::PercentOfSeason {
+                season_days: curve
+                    .season_days
+                    .ok_or(format!("Curve {} is missing season_days.", curve.name))?,
+            },
+            "effective_cover" => {
+                points.extend(
+                    curve
+                        .after_cover_points
+                        .iter()
+                        .map(|&(days, kc)| (100.0 + days, kc)),
+                );
+                CurveAxis::EffectiveCover {
+                    days_to_cover: curve.days_to_effective_cover.ok_or(format!(
+                        "Curve {} is missing days_to_effective_cover.",
+                        curve.name
+                    ))?,
+                }
+            }
+            "normalized_gdd" => CurveAxis::NormalizedGdd,
+            other => return Err(format!("Curve {} has unknown axis {}.", curve.name, other).into()),
+        };
+        // A breakpoint at effective cover may be given in both tables
+        points.dedup_by(|b, a| a.0 == b.0 && a.1 == b.1);
+        validate_points(&points).map_err(|e| format!("Curve {}: {}", curve.name, e))?;
+
+        let kc_curve = KcCurve::new(
+            curve.name.clone(),
+            axis,
+            curve.interpolation,
+            points,
+            curve.planting_date,
+            curve.height_m,
+        );
+        result.insert(curve.name, kc_curve);
+    }
+
+    Ok(result)
+}
+
+fn validate_points(points: &[(f32, f32)]) -> Result<(), String> {
+    if points.len() < 2 {
+        return Err("A Kc curve needs at least two breakpoints.".to_string());
+    }
+    if points.windows(2).any(|w| w[1].0 <= w[0].0) {
+        return Err("Kc curve positions must be strictly increasing.".to_string());
+    }
+    if points.iter().any(|p| p.1 < 0.0 || p.1 > 2.0) {
+        return Err("Kc must be between 0 and 2.".to_string());
+    }
+    Ok(())
+}
+
+// Slopes at the breakpoints of a monotone cubic Hermite spline (Fritsch-Carlson), which keeps the curve within the range of
+// neighbouring breakpoints and flat at local extremes.
+fn monotone_slopes(points: &[(f32, f32)]) -> Vec<f32> {
+    let n = points.len();
+    let h: Vec<f32> = points.windows(2).map(|w| w[1].0 - w[0].0).collect();
+    let d: Vec<f32> = points
+        .windows(2)
+        .zip(&h)
+        .map(|(w, h)| (w[1].1 - w[0].1) / h)
+        .collect();
+
+    let mut slopes = vec![0.0; n];
+    slopes[0] = d[0];
+    slopes[n - 1] = d[n - 2];
+    for k in 1..n - 1 {
+        if d[k - 1] * d[k] > 0.0 {
+            let w1 = 2.0 * h[k] + h[k - 1];
+            let w2 = h[k] + 2.0 * h[k - 1];
+            slopes[k] = (w1 + w2) / (w1 / d[k - 1] + w2 / d[k]);
+        }
+    }
+    slopes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planting_date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 4, 20).unwrap()
+    }
+
+    #[test]
+    fn test_linear_curve_interpolation() {
+        let curve = KcCurve::new(
+            "corn".to_string(),
+            CurveAxis::PercentOfSeason { season_days: 120 },
+            Interpolation::Linear,
+            vec![
+                (0.0, 0.3),
+                (20.0, 0.3),
+                (50.0, 1.2),
+                (80.0, 1.2),
+                (100.0, 0.6),
+            ],
+            planting_date(),
+            2.0,
+        );
+
+        assert_eq!(curve.kc_at(-5.0), 0.3);
+        assert!((curve.kc_at(35.0) - 0.75).abs() < 0.001);
+        assert!((curve.kc_at(90.0) - 0.9).abs() < 0.001);
+        assert_eq!(curve.kc_at(120.0), 0.6);
+
+        // 48 days is 40% of the 120 day season
+        let date = planting_date() + chrono::Duration::days(48);
+        assert!((curve.coefficient_from_date(date, None, None, None) - 0.9).abs() < 0.001);
+        assert_eq!(
+            curve.season_end(),
+            Some(planting_date() + chrono::Duration::days(120))
+        );
+    }
+
+    #[test]
+    fn test_cubic_spline_does_not_overshoot() {
+        let curve = KcCurve::new(
+            "corn".to_string(),
+            CurveAxis::Days,
+            Interpolation::CubicSpline,
+            vec![
+                (0.0, 0.2),
+                (30.0, 0.25),
+                (60.0, 1.0),
+                (90.0, 1.05),
+                (120.0, 0.3),
+            ],
+            planting_date(),
+            2.0,
+        );
+
+        for &(x, kc) in curve.points() {
+            assert!((curve.kc_at(x) - kc).abs() < 0.0001);
+        }
+        for day in 0..=120 {
+            let kc = curve.kc_at(day as f32);
+            assert!(
+                (0.2..=1.05).contains(&kc),
+                "Kc {} out of range on day {}",
+                kc,
+                day
+            );
+        }
+        // Smooth rather than linear between breakpoints
+        assert!((curve.kc_at(40.0) - 0.5).abs() > 0.01);
+    }
+
+    #[test]
+    fn test_normalized_gdd_axis() {
+        let curve = KcCurve::new(
+            "corn".to_string(),
+            CurveAxis::NormalizedGdd,
+            Interpolation::Linear,
+            vec![(0.0, 0.3), (0.15, 0.3), (0.45, 1.2), (0.8, 1.2), (1.0, 0.6)],
+            planting_date(),
+            2.0,
+        );
+
+        assert!((curve.kc_at_gdd(900.0, 1500.0) - 1.2).abs() < 0.001);
+        assert!((curve.kc_at_gdd(1350.0, 1500.0) - 0.9).abs() < 0.001);
+        assert_eq!(curve.season_end(), None);
+    }
+
+    #[test]
+    fn test_effective_cover_axis() {
+        let axis = CurveAxis::EffectiveCover { days_to_cover: 60 };
+
+        assert_eq!(axis.position_for_days(30), Some(50.0));
+        assert_eq!(axis.position_for_days(60), Some(100.0));
+        assert_eq!(axis.position_for_days(75), Some(115.0));
+        assert_eq!(CurveAxis::NormalizedGdd.position_for_days(75), None);
+    }
+
+    #[test]
+    fn test_curve_from_four_stage_model() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            planting_date(),
+            2.0,
+        );
+        let curve = KcCurve::from(&cc);
+
+        for day in [0, 10, 35, 50, 75, 110, 120] {
+            let date = planting_date() + chrono::Duration::days(day);
+            let expected = cc.coefficient_from_date(date, Some(4.0), Some(30.0), None);
+            let kc = curve.coefficient_from_date(date, Some(4.0), Some(30.0), None);
+            assert!(
+                (kc - expected).abs() < 0.0001,
+                "Day {}: {} != {}",
+                day,
+                kc,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_kc_curves() {
+        let toml_str = r#"
+            [curves.corn_basal]
+            name = "corn_basal"
+            axis = "effective_cover"
+            interpolation = "cubic_spline"
+            planting_date = "2023-05-01"
+            height_m = 2.0
+            days_to_effective_cover = 70
+            points = [[0, 0.15], [50, 0.5], [100, 0.95]]
+            after_cover_points = [[0, 0.95], [30, 0.95], [60, 0.4]]
+        "#;
+
+        let curves = parse_kc_curves(toml_str).expect("Error parsing curves");
+        let curve = curves.get("corn_basal").expect("Curve not found");
+
+        assert_eq!(curve.axis, CurveAxis::EffectiveCover { days_to_cover: 70 });
+        assert_eq!(curve.points().len(), 5);
+        assert!((curve.kc_at(130.0) - 0.95).abs() < 0.001);
+        assert_eq!(
+            curve.season_end(),
+            Some(NaiveDate::from_ymd_opt(2023, 5, 1).unwrap() + chrono::Duration::days(130))
+        );
+    }
+
+    #[test]
+    fn test_parse_kc_curves_rejects_invalid_curves() {
+        let toml_str = r#"
+            [curves.bad]
+            name = "bad"
+            axis = "days"
+            interpolation = "linear"
+            planting_date = "2023-05-01"
+            height_m = 2.0
+            points = [[0, 0.15], [0, 0.5]]
+        "#;
+
+        assert!(parse_kc_curves(toml_str).is_err());
+    }
+
+    #[test]
+    fn test_load_kc_curves() {
+        let curves = load_kc_curves().expect("Error loading curves");
+        let corn = curves
+            .get("corn_percent_of_season")
+            .expect("Curve not found");
+
+        assert_eq!(corn.axis, CurveAxis::PercentOfSeason { season_days: 120 });
+        assert!((corn.kc_at(60.0) - 1.2).abs() < 0.001);
+    }
+}
@@ -1,11 +1,17 @@
+use crate::kc_adjustment::{adjust_kc_checked, HumidityUnit};
+use crate::kc_result::{stage_fraction, KcInput, KcResult};
+use crate::kcc_gs::GrowthStage;
+
 // Crop Coefficients struct to hold the mean coefficients for each crop stage using growing degree days, it contains the length of the
 // period and the end Kc for each stage, ensure that if you are using Fahrenheit GDD coefficients, then the cumulative GDD should be in Fahrenheit.
+#[derive(Debug, Clone)]
 pub struct CropCoefficientsGdd {
     crop_name: String,
     initial_end_kc: (f32, f32),
     development_end_kc: (f32, f32),
     mid_end_kc: (f32, f32),
     late_end_kc: (f32, f32),
+    max_crop_height: Option<f32>,
 }
 
 impl CropCoefficientsGdd {
@@ -21,12 +27,14 @@ impl CropCoefficientsGdd {
     ///
     /// # Returns
     ///
-    /// A `CropCoefficients` struct initialized with the provided parameters. Panics if any length of period is not positive or if any Kc value exceeds 2.
+    /// A `CropCoefficients` struct initialized with the provided parameters. Panics if any length of period is not positive, if the end of a
+    /// period is before the end of the previous period or if any Kc value exceeds 2.
     pub fn new(crop_name: String, initial_end_kc: (f32, f32), development_end_kc: (f32, f32), mid_end_kc: (f32, f32), late_end_kc: (f32, f32)) -> CropCoefficientsGdd {
         // check if length of period is positive
         if initial_end_kc.0 < 0.0 || development_end_kc.0 < 0.0 || mid_end_kc.0 < 0.0 || late_end_kc.0 < 0.0 {
             panic!("Length of period must be positive.");
         }
+        check_stage_order([initial_end_kc.0, development_end_kc.0, mid_end_kc.0, late_end_kc.0]);
 
         // check if Kc cannot exceed 2
         if initial_end_kc.1 > 2.0 || development_end_kc.1 > 2.0 || mid_end_kc.1 > 2.0 || late_end_kc.1 > 2.0 {
@@ -39,12 +47,197 @@ impl CropCoefficientsGdd {
             development_end_kc,
             mid_end_kc,
             late_end_kc,
+            max_crop_height: None,
+        }
+    }
+
+    /// Sets the mature crop height in meters, which is reached at the end of the development stage and used to derive the
+    /// crop height for the climate adjustment of Kc.
+    ///
+    /// # Parameters
+    ///
+    /// - `max_crop_height`: A `f32` representing the mature crop height in meters. Panics if the height is not positive.
+    ///
+    /// # Returns
+    ///
+    /// The `CropCoefficientsGdd` with the mature crop height set.
+    pub fn with_crop_height(mut self, max_crop_height: f32) -> CropCoefficientsGdd {
+        if max_crop_height <= 0.0 {
+            panic!("Crop height must be positive.");
+        }
+
+        self.max_crop_height = Some(max_crop_height);
+        self
+    }
+
+    /// Returns the name of the crop.
+    pub fn crop_name(&self) -> &str {
+        &self.crop_name
+    }
+
+    /// Returns the cumulative GDD at the end of the late stage.
+    pub fn gdd_to_maturity(&self) -> f32 {
+        self.late_end_kc.0
+    }
+
+    /// Returns the crop height in meters for the given cumulative GDD. The crop grows linearly from emergence height at
+    /// planting to its mature height at the end of the development stage and stays constant afterwards. If no mature
+    /// height was set with `with_crop_height` the default height of 1.391 m is returned for the whole season.
+    ///
+    /// # Parameters
+    ///
+    /// - `cumulative_gdd`: A `f32` representing the cumulative growing degree days since planting.
+    ///
+    /// # Returns
+    ///
+    /// The crop height in meters as a `f32`.
+    pub fn crop_height_at(&self, cumulative_gdd: f32) -> f32 {
+        match self.max_crop_height {
+            Some(max_crop_height) => {
+                let progress = if self.development_end_kc.0 > 0.0 {
+                    cumulative_gdd / self.development_end_kc.0
+                } else {
+                    1.0
+                };
+                crop_height_profile(progress, max_crop_height)
+            }
+            None => DEFAULT_CROP_HEIGHT,
         }
     }
 }
 
+// Crop Coefficients struct to hold the mean coefficients for each crop stage on a normalized GDD scale, the end of each stage is
+// given as a fraction of the GDD from planting to maturity so one parameter set serves hybrids or varieties of different maturity.
+#[derive(Debug, Clone)]
+pub struct CropCoefficientsGddNormalized {
+    crop_name: String,
+    initial_end_kc: (f32, f32),
+    development_end_kc: (f32, f32),
+    mid_end_kc: (f32, f32),
+    late_end_kc: (f32, f32),
+    max_crop_height: Option<f32>,
+}
+
+impl CropCoefficientsGddNormalized {
+    /// Creates a new instance of `CropCoefficientsGddNormalized` with specified parameters for each growth stage.
+    ///
+    /// # Parameters
+    ///
+    /// - `crop_name`: A `String` representing the name of the crop.
+    /// - `initial_end_kc`: A tuple `(f32, f32)` representing the end of the initial growth period as a fraction of the GDD to maturity and the end mean Kc value for this stage.
+    /// - `development_end_kc`: A tuple `(f32, f32)` representing the end of the development growth period as a fraction of the GDD to maturity and the end mean Kc value for this stage.
+    /// - `mid_end_kc`: A tuple `(f32, f32)` representing the end of the mid-season growth period as a fraction of the GDD to maturity and the end mean Kc value for this stage.
+    /// - `late_end_kc`: A tuple `(f32, f32)` representing the end of the late growth period as a fraction of the GDD to maturity and the end mean Kc value for this stage.
+    ///
+    /// # Returns
+    ///
+    /// A `CropCoefficientsGddNormalized` struct initialized with the provided parameters. Panics if any fraction is not between 0 and 1, if
+    /// the end of a period is before the end of the previous period or if any Kc value exceeds 2.
+    pub fn new(crop_name: String, initial_end_kc: (f32, f32), development_end_kc: (f32, f32), mid_end_kc: (f32, f32), late_end_kc: (f32, f32)) -> CropCoefficientsGddNormalized {
+        // check if the end of each period is a fraction of the season
+        if [initial_end_kc.0, development_end_kc.0, mid_end_kc.0, late_end_kc.0].iter().any(|f| !(0.0..=1.0).contains(f)) {
+            panic!("End of period must be a fraction between 0 and 1.");
+        }
+        check_stage_order([initial_end_kc.0, development_end_kc.0, mid_end_kc.0, late_end_kc.0]);
+
+        // check if Kc cannot exceed 2
+        if initial_end_kc.1 > 2.0 || development_end_kc.1 > 2.0 || mid_end_kc.1 > 2.0 || late_end_kc.1 > 2.0 {
+            panic!("Kc cannot exceed 2.");
+        }
+
+        CropCoefficientsGddNormalized {
+            crop_name,
+            initial_end_kc,
+            development_end_kc,
+            mid_end_kc,
+            late_end_kc,
+            max_crop_height: None,
+        }
+    }
+
+    /// Sets the mature crop height in meters, passed on to the `CropCoefficientsGdd` of each hybrid (see `CropCoefficientsGdd::with_crop_height`).
+    pub fn with_crop_height(mut self, max_crop_height: f32) -> CropCoefficientsGddNormalized {
+        if max_crop_height <= 0.0 {
+            panic!("Crop height must be positive.");
+        }
+
+        self.max_crop_height = Some(max_crop_height);
+        self
+    }
+
+    /// Scales the normalized stages to the GDD from planting to maturity of a hybrid or variety.
+    ///
+    /// # Parameters
+    ///
+    /// - `gdd_to_maturity`: A `f32` representing the cumulative GDD from planting to maturity, in the same units as the cumulative GDD
+    ///   that will be used with the result (see `corn_gdd_to_maturity` for corn hybrids).
+    ///
+    /// # Returns
+    ///
+    /// A `CropCoefficientsGdd` with absolute GDD thresholds for the hybrid. Panics if the GDD to maturity is not positive.
+    pub fn for_gdd_to_maturity(&self, gdd_to_maturity: f32) -> CropCoefficientsGdd {
+        if gdd_to_maturity <= 0.0 {
+            panic!("GDD to maturity must be positive.");
+        }
+
+        let scale = |stage: (f32, f32)| (stage.0 * gdd_to_maturity, stage.1);
+        let cc = CropCoefficientsGdd::new(
+            self.crop_name.clone(),
+            scale(self.initial_end_kc),
+            scale(self.development_end_kc),
+            scale(self.mid_end_kc),
+            scale(self.late_end_kc),
+        );
+        match self.max_crop_height {
+            Some(max_crop_height) => cc.with_crop_height(max_crop_height),
+            None => cc,
+        }
+    }
+}
+
+/// Estimates the growing degree days (base 10 °C, 30 °C cap as in `calculate_gdd`) from planting to physiological maturity (black
+/// layer) of a corn hybrid from its relative maturity rating, using the common seed company rule of about 22.5 °F GDD per day of
+/// relative maturity plus an offset. The offset is an empirical fit, not a published constant: about 175 °F GDD reproduces the black
+/// layer ratings of Corn Belt hybrids (2650 °F GDD at 110 days), but it should be calibrated to the GDD ratings of the hybrids grown.
+/// Multiply by 1.8 for Fahrenheit GDD.
+///
+/// # Parameters
+///
+/// - `relative_maturity`: A `f32` representing the relative maturity of the hybrid in days, e.g. 95 to 115 for Nebraska.
+/// - `offset`: A `f32` representing the °F GDD added to 22.5 °F GDD per day of relative maturity.
+///
+/// # Returns
+///
+/// The GDD from planting to maturity in °C days as a `f32`.
+pub fn corn_gdd_to_maturity(relative_maturity: f32, offset: f32) -> f32 {
+    (22.5 * relative_maturity + offset) / 1.8
+}
+
+// Panics if the end of a stage, in cumulative GDD or as a fraction of the GDD to maturity, is before the end of the previous stage.
+fn check_stage_order(stage_ends: [f32; 4]) {
+    if stage_ends.windows(2).any(|ends| ends[1] < ends[0]) {
+        panic!("End of period cannot be before the end of the previous period.");
+    }
+}
+
+// Default crop height in meters used by the climate adjustment when no crop height is known.
+const DEFAULT_CROP_HEIGHT: f32 = 1.391;
+
+// Crop height in meters at emergence, the starting point of the crop height profile.
+const EMERGENCE_CROP_HEIGHT: f32 = 0.05;
+
+/// Returns the crop height for a fraction of the time (days or GDD) from planting to the end of the development stage.
+/// The height grows linearly from the emergence height to `max_crop_height` and is constant after the development stage.
+pub(crate) fn crop_height_profile(progress: f32, max_crop_height: f32) -> f32 {
+    let start_height = EMERGENCE_CROP_HEIGHT.min(max_crop_height);
+    let progress = progress.clamp(0.0, 1.0);
+    start_height + (max_crop_height - start_height) * progress
+}
+
 /// Calculates the crop coefficient (Kc) based on the cumulative growing degree days (GDD) and other optional environmental factors.
 /// It will adjust the Kc for wind speed, relative humidity, and crop height if provided the optional environmental factors.
+/// The Kc of the initial stage is rounded to 2 decimals, see `crop_coefficient_gdd_result` for the Kc with the breakdown and
+/// the adjustment warnings.
 ///
 /// # Parameters
 ///
@@ -52,53 +245,104 @@ impl CropCoefficientsGdd {
 /// - `cc`: A `CropCoefficients` struct containing the crop coefficients for different growth stages.
 /// - `wind_speed`: An `Option<f32>` representing the wind speed in m/s. If not provided, defaults to 2.0 m/s.
 /// - `rh_min`: An `Option<f32>` representing the minimum relative humidity in percentage. If not provided, defaults to 45.0%.
-/// - `crop_height`: An `Option<f32>` representing the crop height in meters. If not provided, defaults to 0.0 m.
+///   A humidity given as a fraction is not converted, use `crop_coefficient_gdd_with_unit` for it.
+/// - `crop_height`: An `Option<f32>` representing a measured crop height in meters. If not provided, the height is taken from
+///   the crop height profile of `cc` (see `CropCoefficientsGdd::crop_height_at`).
 ///
 /// # Returns
 ///
 /// A `(String, f32)` representing the name of the corp and the calculated crop coefficient (Kc) adjusted if given environmental conditions.
 pub fn crop_coefficient_gdd(cumulative_gdd: f32, cc: CropCoefficientsGdd, wind_speed: Option<f32>, rh_min: Option<f32>, crop_height: Option<f32>) -> (String, f32) {
-    let wind_speed = wind_speed.unwrap_or(2.0);
-    let mut rh_min = rh_min.unwrap_or(45.0);
-    let crop_height = crop_height.unwrap_or(1.391);
+    crop_coefficient_gdd_with_unit(cumulative_gdd, cc, wind_speed, rh_min, HumidityUnit::Percent, crop_height)
+}
 
-    if rh_min < 1.0 {
-        rh_min *= 100.0; // Convert to percentage
-    }
+/// Calculates the crop coefficient (Kc) like `crop_coefficient_gdd`, with the minimum relative humidity in the given unit.
+///
+/// # Parameters
+///
+/// - `cumulative_gdd`: A `f32` representing the cumulative growing degree days, which is used to determine the crop growth stage.
+/// - `cc`: A `CropCoefficientsGdd` struct containing the crop coefficients for different growth stages.
+/// - `wind_speed`: An `Option<f32>` representing the wind speed in m/s. If not provided, defaults to 2.0 m/s.
+/// - `rh_min`: An `Option<f32>` representing the minimum relative humidity in `humidity_unit`. If not provided, defaults to 45%.
+/// - `humidity_unit`: The `HumidityUnit` of `rh_min`.
+/// - `crop_height`: An `Option<f32>` representing a measured crop height in meters. If not provided, the height is taken from
+///   the crop height profile of `cc`.
+///
+/// # Returns
+///
+/// A `(String, f32)` representing the name of the corp and the calculated crop coefficient (Kc) adjusted if given environmental conditions.
+pub fn crop_coefficient_gdd_with_unit(cumulative_gdd: f32, cc: CropCoefficientsGdd, wind_speed: Option<f32>, rh_min: Option<f32>, humidity_unit: HumidityUnit, crop_height: Option<f32>) -> (String, f32) {
+    let result = crop_coefficient_gdd_result(cumulative_gdd, &cc, wind_speed, rh_min, humidity_unit, crop_height);
+    let kc = match result.growth_stage {
+        GrowthStage::Initial => (result.kc * 100.0).round() / 100.0, // Kc for initial stage rounded to 2 decimals
+        _ => result.kc,
+    };
+    (result.crop_name, kc)
+}
 
-    if cumulative_gdd <= cc.initial_end_kc.0 {
-        (cc.crop_name, (cc.initial_end_kc.1 * 100.0).round() / 100.0) // Kc for initial stage
+/// Calculates the crop coefficient (Kc) like `crop_coefficient_gdd`, returning a `KcResult` with the growth stage, the fraction
+/// through the stage, the tabular Kc, the wind and humidity adjustment terms and the inputs used, including which were defaults.
+///
+/// # Parameters
+///
+/// - `cumulative_gdd`: A `f32` representing the cumulative growing degree days, which is used to determine the crop growth stage.
+/// - `cc`: A `CropCoefficientsGdd` struct containing the crop coefficients for different growth stages.
+/// - `wind_speed`: An `Option<f32>` representing the wind speed in m/s. If not provided, defaults to 2.0 m/s.
+/// - `rh_min`: An `Option<f32>` representing the minimum relative humidity in `humidity_unit`. If not provided, defaults to 45%.
+/// - `humidity_unit`: The `HumidityUnit` of `rh_min`. A value that does not fit the unit is flagged with an
+///   `AdjustmentWarning::AmbiguousHumidityUnit` in the result instead of being converted.
+/// - `crop_height`: An `Option<f32>` representing a measured crop height in meters. If not provided, the height is taken from
+///   the crop height profile of `cc`.
+///
+/// # Returns
+///
+/// The `KcResult` with the breakdown of the calculated Kc.
+pub fn crop_coefficient_gdd_result(cumulative_gdd: f32, cc: &CropCoefficientsGdd, wind_speed: Option<f32>, rh_min: Option<f32>, humidity_unit: HumidityUnit, crop_height: Option<f32>) -> KcResult {
+    let default_rh_min = match humidity_unit {
+        HumidityUnit::Percent => 45.0,
+        HumidityUnit::Fraction => 0.45,
+    };
+    let wind_speed = KcInput::from_option(wind_speed, 2.0);
+    let rh_min = KcInput::from_option(rh_min, default_rh_min);
+    let crop_height = KcInput::from_option(crop_height, cc.crop_height_at(cumulative_gdd));
+
+    let (growth_stage, stage_start, stage_end) = if cumulative_gdd <= cc.initial_end_kc.0 {
+        (GrowthStage::Initial, 0.0, cc.initial_end_kc.0)
     } else if cumulative_gdd <= cc.development_end_kc.0 {
-        // Interpolation between initial and development stages
-        (cc.crop_name, ((cc.initial_end_kc.1 + (cc.development_end_kc.1 - cc.initial_end_kc.1) * ((cumulative_gdd - cc.initial_end_kc.0) / (cc.development_end_kc.0 - cc.initial_end_kc.0))) * 100.0) / 100.0) // Kc for development stage
+        (GrowthStage::Development, cc.initial_end_kc.0, cc.development_end_kc.0)
     } else if cumulative_gdd <= cc.mid_end_kc.0 {
-        // Interpolation between development and mid-season stages
-        let kc_org = cc.development_end_kc.1 + (cc.mid_end_kc.1 - cc.development_end_kc.1) * ((cumulative_gdd - cc.development_end_kc.0) / (cc.mid_end_kc.0 - cc.development_end_kc.0));
-        // Adjust Kc based on crop height and wind speed to compensate for arid and windy conditions
-        (cc.crop_name, (adjust_kc(kc_org, wind_speed, rh_min, crop_height) * 100.0) / 100.0) // Kc for mid-season stage
+        (GrowthStage::Mid, cc.development_end_kc.0, cc.mid_end_kc.0)
     } else {
-        // Interpolation between mid-season and end stages
-        let kc_org = cc.mid_end_kc.1 - (cc.mid_end_kc.1 - cc.late_end_kc.1) * ((cumulative_gdd - cc.late_end_kc.0) / (cc.mid_end_kc.0 - cc.late_end_kc.0));
-        // let kc_org = (cc.development_end_kc.1 - ((cumulative_gdd - cc.mid_end_kc.0) / cc.late_end_kc.0)).max(cc.late_end_kc.1);
-        // Adjust Kc based on crop height and wind speed if it's larger than 0.45
-        if kc_org > 0.45 {
-            (cc.crop_name, (adjust_kc(kc_org, wind_speed, rh_min, crop_height) * 100.0) / 100.0) // Kc for end stage
-        } else {
-            (cc.crop_name, (kc_org * 100.0) / 100.0) // Kc for end stage with default adjustment if Kc is less than 0.45
+        (GrowthStage::Late, cc.mid_end_kc.0, cc.late_end_kc.0)
+    };
+    let fraction = stage_fraction(cumulative_gdd, stage_start, stage_end);
+
+    let (kc_org, adjust) = match growth_stage {
+        GrowthStage::Initial => (cc.initial_end_kc.1, false), // Kc for initial stage
+        // Interpolation between initial and development stages
+        GrowthStage::Development => (cc.initial_end_kc.1 + (cc.development_end_kc.1 - cc.initial_end_kc.1) * fraction, false),
+        // Interpolation between development and mid-season stages, adjusted based on crop height and wind speed to compensate for arid and windy conditions
+        GrowthStage::Mid => (cc.development_end_kc.1 + (cc.mid_end_kc.1 - cc.development_end_kc.1) * fraction, true),
+        GrowthStage::Late => {
+            // Interpolation between mid-season and end stages, adjusted based on crop height and wind speed if it's larger than 0.45
+            let kc_org = cc.mid_end_kc.1 - (cc.mid_end_kc.1 - cc.late_end_kc.1) * fraction;
+            (kc_org, kc_org > 0.45)
         }
-    }
+    };
+
+    KcResult::evaluate(cc.crop_name.clone(), growth_stage, fraction, kc_org, wind_speed, rh_min, humidity_unit, crop_height, adjust)
 }
 
+// Adjusts Kc for wind speed, minimum relative humidity in percent and crop height, clamping the inputs to the FAO-56 valid
+// ranges (see `adjust_kc_checked`).
 pub(crate) fn adjust_kc(kc_original: f32, wind_speed: f32, rh_min: f32, crop_height: f32) -> f32 {
-    let term1 = 0.04 * (wind_speed - 2.0);
-    let term2 = 0.0004 * (rh_min - 45.0);
-    let adjustment = (term1 - term2) * (crop_height / 3.0).powf(0.3);
-    kc_original + adjustment
+    adjust_kc_checked(kc_original, wind_speed, rh_min, HumidityUnit::Percent, crop_height).kc
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kc_adjustment::AdjustmentWarning;
 
     #[test]
     // Should return the initial stage Kc when cumulative GDD is exactly at the initial_end_kc threshold
@@ -194,6 +438,24 @@ mod tests {
         assert!((kc - 0.85).abs() < 0.01, "Expected Kc to be interpolated correctly between mid and end stages");
     }
 
+    #[test]
+    // Should decrease Kc from the mid-season to the end value through the late stage, not only at its midpoint
+    fn test_crop_coefficient_gdd_late_stage_follows_gdd() {
+        let crop_coefficients = || CropCoefficientsGdd::new(
+            "TestCrop".to_string(),
+            (100.0, 0.3),  // initial_end_kc
+            (200.0, 0.5),  // development_end_kc
+            (300.0, 1.0),  // mid_end_kc
+            (400.0, 0.7),  // late_end_kc
+        );
+
+        let (_, kc) = crop_coefficient_gdd(375.0, crop_coefficients(), None, None, None);
+        assert!((kc - 0.775).abs() < 0.001);
+
+        let (_, kc) = crop_coefficient_gdd(450.0, crop_coefficients(), None, None, None);
+        assert!((kc - 0.7).abs() < 0.001);
+    }
+
     #[test]
     // Should adjust Kc for mid-season stage based on wind speed, relative humidity, and crop height when environmental factors are provided
     fn test_adjust_kc_mid_season_with_environmental_factors() {
@@ -267,7 +529,7 @@ mod tests {
     }
 
     #[test]
-    // Should convert relative humidity to percentage if provided as a decimal less than 1
+    // Should convert relative humidity to percentage if provided as a fraction
     fn test_crop_coefficient_gdd_with_rh_as_decimal() {
         let cc = CropCoefficientsGdd::new(
             "Wheat".to_string(),
@@ -282,7 +544,7 @@ mod tests {
         let rh_min = Some(0.45); // Relative humidity as a decimal
         let crop_height = Some(1.0);
 
-        let (crop_name, kc) = crop_coefficient_gdd(cumulative_gdd, cc, wind_speed, rh_min, crop_height);
+        let (crop_name, kc) = crop_coefficient_gdd_with_unit(cumulative_gdd, cc, wind_speed, rh_min, HumidityUnit::Fraction, crop_height);
 
         assert_eq!(crop_name, "Wheat");
         assert!((kc - 0.862).abs() < 0.01); // Expected Kc value after adjustment
@@ -311,4 +573,133 @@ mod tests {
         assert_eq!(name, crop_name);
         assert!((kc - 0.3).abs() < 0.01, "Expected Kc to be 0.3, got {}", kc);
     }
-}
\ No newline at end of file
+
+    #[test]
+    // Should grow the crop height through the development stage and keep it constant afterwards
+    fn test_crop_height_at_follows_growth_stages() {
+        let cc = CropCoefficientsGdd::new(
+            "Corn".to_string(),
+            (200.0, 0.3),
+            (500.0, 1.15),
+            (800.0, 1.2),
+            (1000.0, 0.5),
+        )
+        .with_crop_height(2.0);
+
+        assert!((cc.crop_height_at(0.0) - 0.05).abs() < 0.001);
+        assert!((cc.crop_height_at(250.0) - 1.025).abs() < 0.001);
+        assert!((cc.crop_height_at(500.0) - 2.0).abs() < 0.001);
+        assert!((cc.crop_height_at(900.0) - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    // Should use the mature crop height in the climate adjustment when no measured height is given
+    fn test_crop_coefficient_gdd_uses_crop_height_profile() {
+        let cc = CropCoefficientsGdd::new(
+            "Corn".to_string(),
+            (200.0, 0.3),
+            (500.0, 1.15),
+            (800.0, 1.2),
+            (1000.0, 0.5),
+        )
+        .with_crop_height(3.0);
+
+        let (_, kc) = crop_coefficient_gdd(800.0, cc, Some(4.0), Some(45.0), None);
+
+        // (0.04 * (4 - 2)) * (3 / 3)^0.3 = 0.08
+        assert!((kc - 1.28).abs() < 0.001);
+    }
+
+    #[test]
+    // Should use the default crop height when no mature crop height is set
+    fn test_crop_height_at_without_crop_height() {
+        let cc = CropCoefficientsGdd::new(
+            "Corn".to_string(),
+            (200.0, 0.3),
+            (500.0, 1.15),
+            (800.0, 1.2),
+            (1000.0, 0.5),
+        );
+
+        assert!((cc.crop_height_at(0.0) - 1.391).abs() < 0.001);
+        assert!((cc.crop_height_at(900.0) - 1.391).abs() < 0.001);
+    }
+
+    #[test]
+    // Should break down the Kc into the tabular value and the adjustment terms, flagging defaulted inputs
+    fn test_crop_coefficient_gdd_result() {
+        let cc = CropCoefficientsGdd::new(
+            "Corn".to_string(),
+            (100.0, 0.3),
+            (200.0, 0.7),
+            (300.0, 1.2),
+            (400.0, 0.5),
+        )
+        .with_crop_height(3.0);
+
+        let result = crop_coefficient_gdd_result(325.0, &cc, Some(4.0), None, HumidityUnit::Percent, None);
+
+        assert_eq!(result.crop_name, "Corn");
+        assert_eq!(result.growth_stage, GrowthStage::Late);
+        assert!((result.stage_fraction - 0.25).abs() < 0.001);
+        assert!((result.tabular_kc - 1.025).abs() < 0.001);
+        assert!((result.wind_term - 0.08).abs() < 0.001);
+        assert_eq!(result.humidity_term, 0.0);
+        assert!(result.rh_min.is_default);
+        assert!(result.crop_height.is_default);
+        assert!((result.kc - 1.105).abs() < 0.001);
+
+        // A fraction declared as percent is flagged in the result, not converted
+        let result = crop_coefficient_gdd_result(325.0, &cc, None, Some(0.3), HumidityUnit::Percent, None);
+        assert!(matches!(result.warnings[0], AdjustmentWarning::AmbiguousHumidityUnit { .. }));
+        let result = crop_coefficient_gdd_result(325.0, &cc, None, Some(0.3), HumidityUnit::Fraction, None);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    // Should scale one normalized parameter set to hybrids of different maturity
+    fn test_normalized_gdd_for_hybrids() {
+        let corn = CropCoefficientsGddNormalized::new(
+            "Corn".to_string(),
+            (0.15, 0.3),
+            (0.45, 1.2),
+            (0.8, 1.2),
+            (1.0, 0.6),
+        );
+
+        let early = corn.for_gdd_to_maturity(corn_gdd_to_maturity(95.0, 175.0));
+        let late = corn.for_gdd_to_maturity(corn_gdd_to_maturity(115.0, 175.0));
+
+        // The same cumulative GDD is later in the season of the early hybrid
+        let (_, kc_early) = crop_coefficient_gdd(1200.0, early.clone(), None, None, None);
+        let (_, kc_late) = crop_coefficient_gdd(1200.0, late, None, None, None);
+        assert!(kc_early < kc_late);
+
+        // Half way through the development stage of each hybrid has the same Kc
+        let gdd = 0.3 * corn_gdd_to_maturity(95.0, 175.0);
+        let (_, kc) = crop_coefficient_gdd(gdd, early, None, None, None);
+        assert!((kc - 0.75).abs() < 0.001);
+    }
+
+    #[test]
+    #[should_panic(expected = "End of period cannot be before the end of the previous period.")]
+    // Should reject normalized stages that end before the previous stage
+    fn test_normalized_gdd_rejects_unordered_stages() {
+        CropCoefficientsGddNormalized::new("Corn".to_string(), (0.15, 0.3), (0.8, 1.2), (0.45, 1.2), (1.0, 0.6));
+    }
+
+    #[test]
+    #[should_panic(expected = "End of period cannot be before the end of the previous period.")]
+    // Should reject GDD stages that end before the previous stage
+    fn test_gdd_rejects_unordered_stages() {
+        CropCoefficientsGdd::new("Corn".to_string(), (200.0, 0.3), (500.0, 1.15), (1000.0, 1.2), (800.0, 0.5));
+    }
+
+    #[test]
+    // Should estimate the GDD to maturity from relative maturity
+    fn test_corn_gdd_to_maturity() {
+        assert!((corn_gdd_to_maturity(110.0, 175.0) * 1.8 - 2650.0).abs() < 0.1);
+        assert!((corn_gdd_to_maturity(110.0, 225.0) * 1.8 - 2700.0).abs() < 0.1);
+        assert!(corn_gdd_to_maturity(115.0, 175.0) > corn_gdd_to_maturity(95.0, 175.0));
+    }
+}
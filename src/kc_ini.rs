@@ -0,0 +1,287 @@
+use crate::kcc_gs::CropCoefficientsGs;
+
+// Broad soil texture class of the evaporating surface layer, used for heavy infiltration events where the total
+// evaporable water depends on the soil (FAO-56 Figure 30).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoilTexture {
+    Coarse,
+    Medium,
+    Fine,
+}
+
+// Irrigation or wetting method, determining the fraction of the soil surface wetted (FAO-56 Table 20).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IrrigationType {
+    Precipitation,
+    Sprinkler,
+    Basin,
+    Border,
+    Furrow,
+    FurrowWideBed,
+    FurrowAlternate,
+    Trickle,
+}
+
+impl IrrigationType {
+    /// Returns the fraction of the soil surface wetted (fw) by the irrigation type, using the middle of the ranges given
+    /// in FAO-56 Table 20.
+    pub fn wetted_fraction(&self) -> f32 {
+        match self {
+            IrrigationType::Precipitation
+            | IrrigationType::Sprinkler
+            | IrrigationType::Basin
+            | IrrigationType::Border => 1.0,
+            IrrigationType::Furrow => 0.8,
+            IrrigationType::FurrowWideBed => 0.5,
+            IrrigationType::FurrowAlternate => 0.4,
+            IrrigationType::Trickle => 0.35,
+        }
+    }
+}
+
+// Wetting conditions during the initial stage, used to derive Kc_ini in place of the tabulated value. The interval is the
+// average number of days between wetting events, the infiltration depth in mm is the average depth of a wetting event over
+// the whole field and ETo is the mean reference evapotranspiration of the initial stage in mm/day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InitialWetting {
+    pub interval_days: f32,
+    pub infiltration_depth: f32,
+    pub eto: f32,
+    pub soil_texture: SoilTexture,
+    pub irrigation_type: IrrigationType,
+}
+
+impl InitialWetting {
+    pub fn new(
+        interval_days: f32,
+        infiltration_depth: f32,
+        eto: f32,
+        soil_texture: SoilTexture,
+        irrigation_type: IrrigationType,
+    ) -> Self {
+        InitialWetting {
+            interval_days,
+            infiltration_depth,
+            eto,
+            soil_texture,
+            irrigation_type,
+        }
+    }
+
+    /// Returns Kc_ini for the wetting conditions, see `kc_ini`.
+    pub fn kc_ini(&self) -> f32 {
+        kc_ini(
+            self.interval_days,
+            self.infiltration_depth,
+            self.eto,
+            self.soil_texture,
+            self.irrigation_type.wetted_fraction(),
+        )
+    }
+}
+
+impl CropCoefficientsGs {
+    /// Returns the crop coefficients with the tabulated Kc_ini replaced by the value derived from the wetting conditions of
+    /// the initial stage (see `kc_ini`).
+    ///
+    /// # Parameters
+    ///
+    /// - `wetting`: The `InitialWetting` conditions of the initial stage.
+    ///
+    /// # Returns
+    ///
+    /// The `CropCoefficientsGs` with the derived Kc_ini.
+    pub fn with_initial_wetting(mut self, wetting: &InitialWetting) -> CropCoefficientsGs {
+        self.initial_end_kc.kc = wetting.kc_ini();
+        self
+    }
+}
+
+// Potential evaporation rate from the wet soil surface relative to ETo during the initial stage.
+const ESO_RATIO: f32 = 1.15;
+
+/// Calculates Kc_ini for light infiltration events of 10 mm or less (FAO-56 Figure 29), valid for all soil textures.
+///
+/// # Parameters
+///
+/// - `interval_days`: The average interval between wetting events in days.
+/// - `eto`: The mean reference evapotranspiration of the initial stage in mm/day.
+///
+/// # Returns
+///
+/// Kc_ini as a `f32`, between 0 and 1.15.
+pub fn kc_ini_light_infiltration(interval_days: f32, eto: f32) -> f32 {
+    let tew = 10.0;
+    let rew = (6.0 / eto.sqrt()).clamp(2.5, 7.0);
+    kc_ini_drying_cycle(interval_days, eto, tew, rew)
+}
+
+/// Calculates Kc_ini for heavy infiltration events of 40 mm or more (FAO-56 Figure 30), where the total evaporable water
+/// depends on the soil texture.
+///
+/// # Parameters
+///
+/// - `interval_days`: The average interval between wetting events in days.
+/// - `eto`: The mean reference evapotranspiration of the initial stage in mm/day.
+/// - `soil_texture`: The `SoilTexture` of the evaporating surface layer.
+///
+/// # Returns
+///
+/// Kc_ini as a `f32`, between 0 and 1.15.
+pub fn kc_ini_heavy_infiltration(interval_days: f32, eto: f32, soil_texture: SoilTexture) -> f32 {
+    let (tew, rew) = match soil_texture {
+        SoilTexture::Coarse => {
+            let tew = (7.0 * eto.sqrt()).min(15.0);
+            (tew, (tew - 0.01).min(6.0))
+        }
+        SoilTexture::Medium | SoilTexture::Fine => {
+            let tew = (13.0 * eto.sqrt()).min(28.0);
+            (tew, (tew - 0.01).min(9.0))
+        }
+    };
+    kc_ini_drying_cycle(interval_days, eto, tew, rew)
+}
+
+/// Calculates Kc_ini from the average interval between wetting events, the infiltration depth and ETo.
+///
+/// Infiltration depths between 10 and 40 mm are interpolated between the light and heavy infiltration curves (FAO-56
+/// Eq. 59). When only part of the surface is wetted, the infiltration depth is taken over the wetted area and Kc_ini is
+/// reduced by the wetted fraction (FAO-56 Eq. 60).
+///
+/// # Parameters
+///
+/// - `interval_days`: The average interval between wetting events in days.
+/// - `infiltration_depth`: The average infiltration depth of a wetting event over the whole field in mm.
+/// - `eto`: The mean reference evapotranspiration of the initial stage in mm/day.
+/// - `soil_texture`: The `SoilTexture` of the evaporating surface layer.
+/// - `wetted_fraction`: The fraction of the soil surface wetted (fw), see `IrrigationType::wetted_fraction`.
+///
+/// # Returns
+///
+/// Kc_ini as a `f32`. Panics if the wetted fraction is not between 0 and 1.
+pub fn kc_ini(
+    interval_days: f32,
+    infiltration_depth: f32,
+    eto: f32,
+    soil_texture: SoilTexture,
+    wetted_fraction: f32,
+) -> f32 {
+    if wetted_fraction <= 0.0 || wetted_fraction > 1.0 {
+        panic!("Wetted fraction must be greater than 0 and no more than 1.");
+    }
+
+    let wetted_depth = infiltration_depth / wetted_fraction;
+    let kc_light = kc_ini_light_infiltration(interval_days, eto);
+    let kc = if wetted_depth <= 10.0 {
+        kc_light
+    } else {
+        let kc_heavy = kc_ini_heavy_infiltration(interval_days, eto, soil_texture);
+        if wetted_depth >= 40.0 {
+            kc_heavy
+        } else {
+            kc_light + (wetted_depth - 10.0) / (40.0 - 10.0) * (kc_heavy - kc_light)
+        }
+    };
+
+    wetted_fraction * kc
+}
+
+// Average Kc over a drying cycle of `interval_days`, with stage 1 evaporation at the potential rate until the readily
+// evaporable water (rew) is depleted and a falling rate afterwards until the total evaporable water (tew) is depleted.
+fn kc_ini_drying_cycle(interval_days: f32, eto: f32, tew: f32, rew: f32) -> f32 {
+    if eto <= 0.0 {
+        return ESO_RATIO;
+    }
+
+    let eso = ESO_RATIO * eto;
+    let t1 = rew / eso;
+    if interval_days <= t1 {
+        return ESO_RATIO;
+    }
+
+    let exponent = -(interval_days - t1) * eso * (1.0 + rew / (tew - rew)) / tew;
+    let kc = (tew - (tew - rew) * exponent.exp()) / (interval_days * eto);
+    kc.min(ESO_RATIO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_kc_ini_light_infiltration() {
+        let kc = kc_ini_light_infiltration(4.0, 5.0);
+        assert!(
+            (kc - 0.477).abs() < 0.005,
+            "Expected Kc_ini of 0.477, got {}",
+            kc
+        );
+
+        // Wetting more often than the stage 1 drying time keeps the surface at the potential rate
+        assert_eq!(kc_ini_light_infiltration(0.25, 5.0), 1.15);
+    }
+
+    #[test]
+    fn test_kc_ini_heavy_infiltration_depends_on_soil() {
+        let coarse = kc_ini_heavy_infiltration(7.0, 5.0, SoilTexture::Coarse);
+        let fine = kc_ini_heavy_infiltration(7.0, 5.0, SoilTexture::Fine);
+        let light = kc_ini_light_infiltration(7.0, 5.0);
+
+        assert!(coarse > light);
+        assert!(fine > coarse);
+        assert!(fine <= 1.15);
+    }
+
+    #[test]
+    fn test_kc_ini_interpolates_between_light_and_heavy() {
+        let light = kc_ini_light_infiltration(7.0, 4.0);
+        let heavy = kc_ini_heavy_infiltration(7.0, 4.0, SoilTexture::Medium);
+
+        assert_eq!(kc_ini(7.0, 8.0, 4.0, SoilTexture::Medium, 1.0), light);
+        assert_eq!(kc_ini(7.0, 50.0, 4.0, SoilTexture::Medium, 1.0), heavy);
+        let kc = kc_ini(7.0, 25.0, 4.0, SoilTexture::Medium, 1.0);
+        assert!((kc - (light + heavy) / 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_kc_ini_partial_wetting() {
+        // 20 mm applied by trickle infiltrates 57 mm over the wetted area, a heavy event on 35 % of the surface
+        let wetting =
+            InitialWetting::new(7.0, 20.0, 5.0, SoilTexture::Medium, IrrigationType::Trickle);
+        let expected = 0.35 * kc_ini_heavy_infiltration(7.0, 5.0, SoilTexture::Medium);
+
+        assert!((wetting.kc_ini() - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_with_initial_wetting_replaces_kc_ini() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        );
+        let wetting = InitialWetting::new(
+            4.0,
+            10.0,
+            5.0,
+            SoilTexture::Medium,
+            IrrigationType::Sprinkler,
+        );
+
+        let cc = cc.with_initial_wetting(&wetting);
+        let kc = cc.coefficient_from_date(
+            NaiveDate::from_ymd_opt(2023, 4, 25).unwrap(),
+            None,
+            None,
+            None,
+        );
+
+        assert!((cc.initial_end_kc.kc - 0.477).abs() < 0.005);
+        assert_eq!(kc, cc.initial_end_kc.kc);
+    }
+}
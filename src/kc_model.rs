@@ -0,0 +1,320 @@
+use crate::gdd::calculate_gdd;
+use crate::kc_adjustment::HumidityUnit;
+use crate::kc_curve::KcCurve;
+use crate::kc_gdd::{crop_coefficient_gdd_result, CropCoefficientsGdd};
+use crate::kc_result::KcResult;
+use crate::kcc_gs::{CropCoefficientsGs, GrowthStage};
+use crate::weather::WeatherRecord;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+// Crop coefficient model that gives a daily Kc by calendar date, implemented by the four-stage `CropCoefficientsGs` and the
+// breakpoint `KcCurve` so either can be used for a season.
+pub trait KcModel {
+    /// Returns the name of the crop.
+    fn crop_name(&self) -> &str;
+
+    /// Returns the planting date of the crop.
+    fn planting_date(&self) -> NaiveDate;
+
+    /// Returns the last day of the season, if the model defines the season in days.
+    fn season_end(&self) -> Option<NaiveDate>;
+
+    /// Calculates the crop coefficient (Kc) on a date, adjusted for wind speed in m/s, minimum relative humidity in % and
+    /// crop height in meters where the model applies the climate adjustment.
+    fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32;
+
+    /// Returns the growth stage of the crop on a date, if the model has growth stages.
+    fn growth_stage_on(&self, _date: NaiveDate) -> Option<GrowthStage> {
+        None
+    }
+
+    /// Calculates the Kc on a date like `coefficient_from_date` with the breakdown of the calculation, if the model
+    /// provides one.
+    fn kc_result_from_date(
+        &self,
+        _date: NaiveDate,
+        _wind_speed: Option<f32>,
+        _rh_min: Option<f32>,
+        _crop_height: Option<f32>,
+    ) -> Option<KcResult> {
+        None
+    }
+}
+
+impl KcModel for CropCoefficientsGs {
+    fn crop_name(&self) -> &str {
+        &self.crop_name
+    }
+
+    fn planting_date(&self) -> NaiveDate {
+        self.planting_date
+    }
+
+    fn season_end(&self) -> Option<NaiveDate> {
+        Some(self.planting_date + chrono::Duration::days(self.late_end_kc.days as i64))
+    }
+
+    fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        CropCoefficientsGs::coefficient_from_date(self, date, wind_speed, rh_min, crop_height)
+    }
+
+    fn growth_stage_on(&self, date: NaiveDate) -> Option<GrowthStage> {
+        Some(CropCoefficientsGs::growth_stage_on(self, date))
+    }
+
+    fn kc_result_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> Option<KcResult> {
+        Some(CropCoefficientsGs::kc_result_from_date(
+            self,
+            date,
+            wind_speed,
+            rh_min,
+            crop_height,
+        ))
+    }
+}
+
+impl KcModel for KcCurve {
+    fn crop_name(&self) -> &str {
+        &self.crop_name
+    }
+
+    fn planting_date(&self) -> NaiveDate {
+        self.planting_date
+    }
+
+    fn season_end(&self) -> Option<NaiveDate> {
+        KcCurve::season_end(self)
+    }
+
+    fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        KcCurve::coefficient_from_date(self, date, wind_speed, rh_min, crop_height)
+    }
+}
+
+// GDD based crop coefficients on calendar dates, with the GDD accumulated from the planting date with the daily temperatures
+// of a weather series. After the last weather record the GDD stays at the last accumulated value, and the season ends on the
+// day the GDD reaches the end of the late stage.
+#[derive(Debug, Clone)]
+pub struct GddKcModel {
+    pub coefficients: CropCoefficientsGdd,
+    pub planting_date: NaiveDate,
+    pub base_temp: f32,
+    cumulative_gdd: BTreeMap<NaiveDate, f32>,
+}
+
+impl GddKcModel {
+    /// Creates a new `GddKcModel`, accumulating the GDD (see `calculate_gdd`) of the weather records from the planting date.
+    ///
+    /// # Parameters
+    ///
+    /// - `coefficients`: The `CropCoefficientsGdd` of the crop, with the stage ends in cumulative GDD of the same unit as the
+    ///   temperatures.
+    /// - `planting_date`: The planting date, the first day of the accumulation.
+    /// - `base_temp`: The base temperature of the GDD.
+    /// - `weather`: A slice of `WeatherRecord` in date order with the daily minimum and maximum temperatures.
+    ///
+    /// # Returns
+    ///
+    /// The `GddKcModel` of the crop.
+    pub fn new(
+        coefficients: CropCoefficientsGdd,
+        planting_date: NaiveDate,
+        base_temp: f32,
+        weather: &[WeatherRecord],
+    ) -> Self {
+        let mut total = 0.0;
+        let cumulative_gdd = weather
+            .iter()
+            .filter(|r| r.date >= planting_date)
+            .map(|r| {
+                total += calculate_gdd(r.max_temp, r.min_temp, base_temp);
+                (r.date, total)
+            })
+            .collect();
+
+        GddKcModel {
+            coefficients,
+            planting_date,
+            base_temp,
+            cumulative_gdd,
+        }
+    }
+
+    /// Returns the GDD accumulated from planting through a date, 0 before planting.
+    pub fn cumulative_gdd_on(&self, date: NaiveDate) -> f32 {
+        self.cumulative_gdd
+            .range(..=date)
+            .next_back()
+            .map_or(0.0, |(_, gdd)| *gdd)
+    }
+
+    // Kc with the breakdown of the calculation at the GDD accumulated through the date
+    fn kc_result_on(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> KcResult {
+        crop_coefficient_gdd_result(
+            self.cumulative_gdd_on(date),
+            &self.coefficients,
+            wind_speed,
+            rh_min,
+            HumidityUnit::Percent,
+            crop_height,
+        )
+    }
+}
+
+impl KcModel for GddKcModel {
+    fn crop_name(&self) -> &str {
+        self.coefficients.crop_name()
+    }
+
+    fn planting_date(&self) -> NaiveDate {
+        self.planting_date
+    }
+
+    // First day the accumulated GDD reaches maturity, unknown if the weather ends before
+    fn season_end(&self) -> Option<NaiveDate> {
+        let maturity = self.coefficients.gdd_to_maturity();
+        self.cumulative_gdd
+            .iter()
+            .find(|(_, gdd)| **gdd >= maturity)
+            .map(|(date, _)| *date)
+    }
+
+    fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        self.kc_result_on(date, wind_speed, rh_min, crop_height).kc
+    }
+
+    fn growth_stage_on(&self, date: NaiveDate) -> Option<GrowthStage> {
+        Some(self.kc_result_on(date, None, None, None).growth_stage)
+    }
+
+    fn kc_result_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> Option<KcResult> {
+        Some(self.kc_result_on(date, wind_speed, rh_min, crop_height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kc_curve::{CurveAxis, Interpolation};
+
+    // Mean Kc over the season of any Kc model
+    fn mean_kc(model: &dyn KcModel) -> f32 {
+        let season_end = model.season_end().expect("Season end not defined");
+        let days = season_end
+            .signed_duration_since(model.planting_date())
+            .num_days();
+        let total: f32 = (0..=days)
+            .map(|day| {
+                let date = model.planting_date() + chrono::Duration::days(day);
+                model.coefficient_from_date(date, None, None, None)
+            })
+            .sum();
+        total / (days + 1) as f32
+    }
+
+    #[test]
+    fn test_kc_models_are_interchangeable() {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 20).unwrap();
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            planting_date,
+            2.0,
+        );
+        let curve = KcCurve::new(
+            "corn".to_string(),
+            CurveAxis::Days,
+            Interpolation::Linear,
+            vec![
+                (0.0, 0.3),
+                (20.0, 0.3),
+                (50.0, 1.2),
+                (100.0, 1.2),
+                (120.0, 0.6),
+            ],
+            planting_date,
+            2.0,
+        );
+
+        assert_eq!(KcModel::season_end(&cc), KcModel::season_end(&curve));
+        assert_eq!(KcModel::crop_name(&curve), "corn");
+        assert!((mean_kc(&cc) - mean_kc(&curve)).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_gdd_kc_model_accumulates_weather() {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+        let cc = CropCoefficientsGdd::new(
+            "corn".to_string(),
+            (100.0, 0.3),
+            (300.0, 1.2),
+            (600.0, 1.2),
+            (800.0, 0.6),
+        );
+        // 10 GDD a day at base 10 °C
+        let weather: Vec<WeatherRecord> = (0..100)
+            .map(|day| WeatherRecord::new(planting_date + chrono::Duration::days(day), 25.0, 15.0))
+            .collect();
+        let model = GddKcModel::new(cc, planting_date, 10.0, &weather);
+
+        assert_eq!(
+            model.cumulative_gdd_on(planting_date.pred_opt().unwrap()),
+            0.0
+        );
+        assert_eq!(model.cumulative_gdd_on(planting_date), 10.0);
+        assert_eq!(
+            model.season_end(),
+            Some(planting_date + chrono::Duration::days(79))
+        );
+
+        let date = planting_date + chrono::Duration::days(40);
+        assert_eq!(model.growth_stage_on(date), Some(GrowthStage::Mid));
+        assert!((model.coefficient_from_date(date, None, None, None) - 1.2).abs() < 0.0001);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::kc_adjustment::{adjust_kc_checked, AdjustmentWarning, HumidityUnit};
+use crate::kcc_gs::GrowthStage;
+
+// Input value used for a Kc calculation, flagged when the value was not provided and a default was used instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KcInput {
+    pub value: f32,
+    pub is_default: bool,
+}
+
+impl KcInput {
+    /// Returns the provided value, or the default flagged as such when no value is provided.
+    pub fn from_option(value: Option<f32>, default: f32) -> Self {
+        match value {
+            Some(value) => KcInput {
+                value,
+                is_default: false,
+            },
+            None => KcInput {
+                value: default,
+                is_default: true,
+            },
+        }
+    }
+}
+
+// Breakdown of a crop coefficient (Kc) calculation, with the growth stage, the tabular Kc before adjustment, the wind and
+// humidity terms of the climate adjustment, the inputs used and the final Kc. The adjustment terms are zero when the
+// growth stage is not adjusted for climate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KcResult {
+    pub crop_name: String,
+    pub growth_stage: GrowthStage,
+    pub stage_fraction: f32,
+    pub tabular_kc: f32,
+    pub wind_term: f32,
+    pub humidity_term: f32,
+    pub wind_speed: KcInput,
+    pub rh_min: KcInput,
+    pub crop_height: KcInput,
+    pub warnings: Vec<AdjustmentWarning>,
+    pub kc: f32,
+}
+
+impl KcResult {
+    // Builds the result for a tabular Kc, applying the climate adjustment with the given inputs if `adjust` is set.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn evaluate(
+        crop_name: String,
+        growth_stage: GrowthStage,
+        stage_fraction: f32,
+        tabular_kc: f32,
+        wind_speed: KcInput,
+        rh_min: KcInput,
+        humidity_unit: HumidityUnit,
+        crop_height: KcInput,
+        adjust: bool,
+    ) -> KcResult {
+        let (wind_term, humidity_term, warnings) = if adjust {
+            let adjustment = adjust_kc_checked(
+                tabular_kc,
+                wind_speed.value,
+                rh_min.value,
+                humidity_unit,
+                crop_height.value,
+            );
+            (
+                adjustment.wind_term,
+                adjustment.humidity_term,
+                adjustment.warnings,
+            )
+        } else {
+            (0.0, 0.0, Vec::new())
+        };
+
+        KcResult {
+            crop_name,
+            growth_stage,
+            stage_fraction,
+            tabular_kc,
+            wind_term,
+            humidity_term,
+            wind_speed,
+            rh_min,
+            crop_height,
+            warnings,
+            kc: tabular_kc + wind_term + humidity_term,
+        }
+    }
+
+    /// Returns the total climate adjustment added to the tabular Kc.
+    pub fn adjustment(&self) -> f32 {
+        self.wind_term + self.humidity_term
+    }
+}
+
+// Fraction of the way through a stage, from 0 at the start to 1 at the end. Zero length stages are complete.
+pub(crate) fn stage_fraction(position: f32, stage_start: f32, stage_end: f32) -> f32 {
+    if stage_end <= stage_start {
+        1.0
+    } else {
+        ((position - stage_start) / (stage_end - stage_start)).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kc_input_from_option() {
+        assert_eq!(
+            KcInput::from_option(Some(3.0), 2.0),
+            KcInput {
+                value: 3.0,
+                is_default: false
+            }
+        );
+        assert!(KcInput::from_option(None, 2.0).is_default);
+    }
+
+    #[test]
+    fn test_kc_result_evaluate() {
+        let result = KcResult::evaluate(
+            "corn".to_string(),
+            GrowthStage::Mid,
+            0.5,
+            1.2,
+            KcInput::from_option(Some(4.0), 2.0),
+            KcInput::from_option(None, 45.0),
+            HumidityUnit::Percent,
+            KcInput::from_option(Some(3.0), 1.391),
+            true,
+        );
+
+        assert!((result.wind_term - 0.08).abs() < 0.0001);
+        assert_eq!(result.humidity_term, 0.0);
+        assert!((result.kc - 1.28).abs() < 0.0001);
+        assert!(result.rh_min.is_default);
+
+        let unadjusted = KcResult::evaluate(
+            "corn".to_string(),
+            GrowthStage::Initial,
+            0.5,
+            0.3,
+            KcInput::from_option(Some(4.0), 2.0),
+            KcInput::from_option(None, 45.0),
+            HumidityUnit::Percent,
+            KcInput::from_option(None, 0.05),
+            false,
+        );
+        assert_eq!(unadjusted.kc, 0.3);
+        assert_eq!(unadjusted.adjustment(), 0.0);
+    }
+
+    #[test]
+    fn test_stage_fraction() {
+        assert_eq!(stage_fraction(25.0, 20.0, 30.0), 0.5);
+        assert_eq!(stage_fraction(35.0, 20.0, 30.0), 1.0);
+        assert_eq!(stage_fraction(20.0, 20.0, 20.0), 1.0);
+    }
+}
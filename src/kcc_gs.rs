@@ -1,12 +1,21 @@
-use crate::kc_gdd::adjust_kc;
+use crate::crop_table::{find_variant, BasalKc, RootDepth};
+use crate::kc_adjustment::HumidityUnit;
+use crate::kc_gdd::{adjust_kc, crop_height_profile};
+use crate::kc_result::{stage_fraction, KcInput, KcResult};
+use crate::salinity::SalinityTolerance;
+use crate::stage_climate::{ClimateAdjustment, ClimateMeans, DailyClimate, StageClimate};
+use crate::yield_response::YieldResponse;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::fs;
+use std::path::Path;
+use std::str::FromStr;
 
 // Crop Coefficients GS struct to hold the mean coefficients for each crop stage using growth stage days, it contains the length of the
 // period in days and the end Kc for each stage. Should use the FAO-56 crop coefficients.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CropCoefficientsGs {
     pub crop_name: String,
     pub initial_end_kc: KcStage,
@@ -15,6 +24,12 @@ pub struct CropCoefficientsGs {
     pub late_end_kc: KcStage,
     pub planting_date: NaiveDate,
     pub crop_height: f64,
+    pub yield_response: Option<YieldResponse>,
+    pub salinity_tolerance: Option<SalinityTolerance>,
+    pub basal_kc: Option<BasalKc>,
+    pub root_depth: Option<RootDepth>,
+    pub depletion_fraction: Option<f32>,
+    pub region: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -29,7 +44,8 @@ impl KcStage {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum GrowthStage {
     Initial,
     Development,
@@ -37,15 +53,116 @@ pub enum GrowthStage {
     Late,
 }
 
+impl GrowthStage {
+    pub const ALL: [GrowthStage; 4] = [
+        GrowthStage::Initial,
+        GrowthStage::Development,
+        GrowthStage::Mid,
+        GrowthStage::Late,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GrowthStage::Initial => "initial",
+            GrowthStage::Development => "development",
+            GrowthStage::Mid => "mid",
+            GrowthStage::Late => "late",
+        }
+    }
+}
+
+impl fmt::Display for GrowthStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GrowthStage {
+    type Err = ParseGrowthStageError;
+
+    // Parses the stage names case insensitively, also accepting the FAO-56 names "dev", "mid-season", "late-season" and "end"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "initial" | "ini" => Ok(GrowthStage::Initial),
+            "development" | "dev" => Ok(GrowthStage::Development),
+            "mid" | "mid-season" => Ok(GrowthStage::Mid),
+            "late" | "late-season" | "end" => Ok(GrowthStage::Late),
+            _ => Err(ParseGrowthStageError(s.to_string())),
+        }
+    }
+}
+
+// Error returned when a string is not the name of a growth stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseGrowthStageError(String);
+
+impl fmt::Display for ParseGrowthStageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown growth stage '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseGrowthStageError {}
+
+// Calendar period of a growth stage, the start and end dates are both part of the stage. A stage of zero days ends the day
+// before it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StagePeriod {
+    pub stage: GrowthStage,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub days: u16,
+}
+
+// Position of a date within its growth stage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StageProgress {
+    pub stage: GrowthStage,
+    pub fraction: f32,
+    pub days_remaining: i64,
+}
+
 // Define the Crop struct for individual crop data
 #[derive(Debug, Serialize, Deserialize)]
 struct Crop {
     name: String,
-    k_ini: f64,                   // Initial stage coefficient
-    k_mid: f64,                   // Mid-season coefficient
-    k_end: f64,                   // Late-season coefficient
-    height_m: f64,                // Crop height in meters
-    growth_stages_days: Vec<i32>, // Growth stages in days [initial, dev, mid, late]
+    k_ini: f64,    // Initial stage coefficient
+    k_mid: f64,    // Mid-season coefficient
+    k_end: f64,    // Late-season coefficient
+    height_m: f64, // Crop height in meters
+    #[serde(default)]
+    growth_stages_days: Option<Vec<i32>>, // Growth stages in days [initial, dev, mid, late], absent without FAO-56 lengths
+    #[serde(default)]
+    planting_date: Option<NaiveDate>,
+    #[serde(default)]
+    ky: Option<f64>, // Seasonal yield response factor
+    #[serde(default)]
+    ky_stages: Option<Vec<f64>>, // Yield response factors by stage [initial, dev, mid, late]
+    #[serde(default)]
+    ece_threshold: Option<f64>, // Soil salinity threshold ECe (dS/m)
+    #[serde(default)]
+    ece_slope: Option<f64>, // Yield decline above the threshold (%/(dS/m))
+    #[serde(default)]
+    kcb_ini: Option<f64>, // Basal initial stage coefficient
+    #[serde(default)]
+    kcb_mid: Option<f64>, // Basal mid-season coefficient
+    #[serde(default)]
+    kcb_end: Option<f64>, // Basal late-season coefficient
+    #[serde(default)]
+    root_depth_m: Option<Vec<f64>>, // Maximum rooting depth range [min, max] in meters
+    #[serde(default)]
+    depletion_fraction: Option<f64>, // Depletion fraction p without stress at ETc of 5 mm/day
+    #[serde(default)]
+    region: Option<String>, // Region of the stage lengths
+    #[serde(default)]
+    variants: Vec<StageVariant>, // Regional stage lengths
+}
+
+// Regional stage lengths of a crop
+#[derive(Debug, Serialize, Deserialize)]
+struct StageVariant {
+    region: String,
+    growth_stages_days: Vec<i32>,
     planting_date: NaiveDate,
 }
 
@@ -108,9 +225,30 @@ impl CropCoefficientsGs {
             late_end_kc: late_stage,
             planting_date,
             crop_height,
+            yield_response: None,
+            salinity_tolerance: None,
+            basal_kc: None,
+            root_depth: None,
+            depletion_fraction: None,
+            region: None,
         }
     }
 
+    /// Returns the crop coefficients with the yield response factors (Ky) of the crop, see `yield_response`.
+    pub fn with_yield_response(mut self, yield_response: YieldResponse) -> CropCoefficientsGs {
+        self.yield_response = Some(yield_response);
+        self
+    }
+
+    /// Returns the crop coefficients with the salt tolerance of the crop, see `salinity_adjusted_etc`.
+    pub fn with_salinity_tolerance(
+        mut self,
+        salinity_tolerance: SalinityTolerance,
+    ) -> CropCoefficientsGs {
+        self.salinity_tolerance = Some(salinity_tolerance);
+        self
+    }
+
     /**
     Calculates the crop coefficient (Kc) with linear interpolation for Development and Late stages,
     and optional adjustments for environmental factors in Mid and Late stages.
@@ -125,7 +263,7 @@ impl CropCoefficientsGs {
     - `date`: A `NaiveDate` for which to calculate Kc.
     - `wind_speed`: Optional wind speed in m/s (default: 2.0).
     - `rh_min`: Optional minimum relative humidity in % (default: 45.0).
-    - `crop_height`: Optional crop height in meters (default: 0.4).
+    - `crop_height`: Optional measured crop height in meters (default: the crop height profile, see `crop_height_at`).
 
     # Returns
 
@@ -138,10 +276,242 @@ impl CropCoefficientsGs {
         rh_min: Option<f32>,
         crop_height: Option<f32>,
     ) -> f32 {
-        let days_since_planting = date.signed_duration_since(self.planting_date).num_days() as i64;
+        self.kc_result_from_date(date, wind_speed, rh_min, crop_height)
+            .kc
+    }
+
+    /// Calculates the crop coefficient (Kc) on a date like `coefficient_from_date`, returning a `KcResult` with the growth
+    /// stage, the tabular Kc, the wind and humidity adjustment terms and the inputs used, including which were defaults.
+    ///
+    /// # Parameters
+    ///
+    /// - `date`: A `NaiveDate` for which to calculate Kc.
+    /// - `wind_speed`: Optional wind speed in m/s (default: 2.0).
+    /// - `rh_min`: Optional minimum relative humidity in % (default: 45.0).
+    /// - `crop_height`: Optional measured crop height in meters (default: the crop height profile, see `crop_height_at`).
+    ///
+    /// # Returns
+    ///
+    /// The `KcResult` with the breakdown of the calculated Kc.
+    pub fn kc_result_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> KcResult {
+        let days_since_planting = date.signed_duration_since(self.planting_date).num_days();
+        self.kc_result_at(
+            days_since_planting,
+            wind_speed,
+            rh_min,
+            HumidityUnit::Percent,
+            crop_height,
+        )
+    }
+
+    fn kc_result_at(
+        &self,
+        days_since_planting: i64,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        humidity_unit: HumidityUnit,
+        crop_height: Option<f32>,
+    ) -> KcResult {
         let growth_stage = self.determine_growth_stage(days_since_planting);
+        let (stage_start, stage_end) = self.stage_bounds(growth_stage);
+        let tabular_kc = self.tabular_kc(days_since_planting, growth_stage);
+
+        KcResult::evaluate(
+            self.crop_name.clone(),
+            growth_stage,
+            stage_fraction(
+                days_since_planting as f32,
+                stage_start as f32,
+                stage_end as f32,
+            ),
+            tabular_kc,
+            KcInput::from_option(wind_speed, 2.0),
+            KcInput::from_option(
+                rh_min,
+                match humidity_unit {
+                    HumidityUnit::Percent => 45.0,
+                    HumidityUnit::Fraction => 0.45,
+                },
+            ),
+            humidity_unit,
+            KcInput::from_option(crop_height, self.crop_height_at(days_since_planting)),
+            // Kc of the late stage is only adjusted when it is larger than 0.45 (FAO-56 Eq. 65)
+            match growth_stage {
+                GrowthStage::Mid => true,
+                GrowthStage::Late => tabular_kc > 0.45,
+                _ => false,
+            },
+        )
+    }
+
+    /// Returns the growth stage of the crop on the given date. Dates before planting are in the initial stage and dates
+    /// after the end of the season in the late stage.
+    pub fn growth_stage_on(&self, date: NaiveDate) -> GrowthStage {
+        self.determine_growth_stage(date.signed_duration_since(self.planting_date).num_days())
+    }
+
+    /// Returns the calendar period of each growth stage, in order from the initial to the late stage.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<StagePeriod>` with the first and last date and the length in days of each `GrowthStage`.
+    pub fn stage_timeline(&self) -> Vec<StagePeriod> {
+        GrowthStage::ALL
+            .iter()
+            .map(|&stage| {
+                let (stage_start, stage_end) = self.stage_bounds(stage);
+                // The initial stage includes the planting date, the other stages start the day after the previous stage ends
+                let first_day = if stage == GrowthStage::Initial {
+                    0
+                } else {
+                    stage_start as i64 + 1
+                };
+                StagePeriod {
+                    stage,
+                    start_date: self.planting_date + chrono::Duration::days(first_day),
+                    end_date: self.planting_date + chrono::Duration::days(stage_end as i64),
+                    days: (stage_end as i64 - first_day + 1).max(0) as u16,
+                }
+            })
+            .collect()
+    }
 
-        let mut kc = match growth_stage {
+    /// Returns the growth stage on the given date with the fraction of the stage completed and the days remaining until
+    /// the last day of the stage.
+    ///
+    /// # Parameters
+    ///
+    /// - `date`: A `NaiveDate` for which to determine the stage progress.
+    ///
+    /// # Returns
+    ///
+    /// The `StageProgress` on the date, the days remaining are zero on the last day of the stage and after the season.
+    pub fn stage_progress_on(&self, date: NaiveDate) -> StageProgress {
+        let days_since_planting = date.signed_duration_since(self.planting_date).num_days();
+        let stage = self.determine_growth_stage(days_since_planting);
+        let (stage_start, stage_end) = self.stage_bounds(stage);
+
+        StageProgress {
+            stage,
+            fraction: stage_fraction(
+                days_since_planting as f32,
+                stage_start as f32,
+                stage_end as f32,
+            ),
+            days_remaining: (stage_end as i64 - days_since_planting).max(0),
+        }
+    }
+
+    // First and last day since planting of a growth stage.
+    fn stage_bounds(&self, growth_stage: GrowthStage) -> (u16, u16) {
+        match growth_stage {
+            GrowthStage::Initial => (0, self.initial_end_kc.days),
+            GrowthStage::Development => (self.initial_end_kc.days, self.development_end_kc.days),
+            GrowthStage::Mid => (self.development_end_kc.days, self.mid_end_kc.days),
+            GrowthStage::Late => (self.mid_end_kc.days, self.late_end_kc.days),
+        }
+    }
+
+    /// Returns a copy of the crop coefficients with Kc_mid and Kc_end adjusted for the mean climate of the mid and late
+    /// stages (FAO-56 Eq. 62 and 65). The end of the development stage ramps up to Kc_mid and is shifted with it, and
+    /// Kc_end is only adjusted when it exceeds 0.45.
+    ///
+    /// # Parameters
+    ///
+    /// - `stage_climate`: The `StageClimate` with the mean wind speed and minimum relative humidity of the mid and late stages.
+    ///
+    /// # Returns
+    ///
+    /// A `CropCoefficientsGs` with the adjusted coefficients, which should be queried without a further daily adjustment.
+    pub fn with_stage_climate(&self, stage_climate: &StageClimate) -> CropCoefficientsGs {
+        let crop_height = self.crop_height as f32;
+        let mid_kc = adjust_kc(
+            self.mid_end_kc.kc,
+            stage_climate.mid.wind_speed,
+            stage_climate.mid.rh_min,
+            crop_height,
+        );
+        let late_kc = if self.late_end_kc.kc > 0.45 {
+            adjust_kc(
+                self.late_end_kc.kc,
+                stage_climate.late.wind_speed,
+                stage_climate.late.rh_min,
+                crop_height,
+            )
+        } else {
+            self.late_end_kc.kc
+        };
+
+        let mut adjusted = self.clone();
+        adjusted.development_end_kc.kc += mid_kc - self.mid_end_kc.kc;
+        adjusted.mid_end_kc.kc = mid_kc;
+        adjusted.late_end_kc.kc = late_kc;
+        adjusted
+    }
+
+    /// Calculates the crop coefficient (Kc) for each day of a daily climate series.
+    ///
+    /// With `ClimateAdjustment::Daily` each day is adjusted with its own wind speed and minimum relative humidity as in
+    /// `coefficient_from_date`. With `ClimateAdjustment::StageAveraged` Kc_mid and Kc_end are adjusted once with the mean
+    /// climate of the mid and late stages, so Kc does not jitter from day to day (see `StageClimate::from_series`).
+    ///
+    /// # Parameters
+    ///
+    /// - `series`: A slice of `DailyClimate` observations for the dates to calculate Kc for.
+    /// - `adjustment`: The `ClimateAdjustment` mode.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec<(NaiveDate, f32)>` with the date and Kc for each day in the series.
+    pub fn coefficients_for_series(
+        &self,
+        series: &[DailyClimate],
+        adjustment: ClimateAdjustment,
+    ) -> Vec<(NaiveDate, f32)> {
+        match adjustment {
+            ClimateAdjustment::Daily => series
+                .iter()
+                .map(|c| {
+                    (
+                        c.date,
+                        self.coefficient_from_date(
+                            c.date,
+                            Some(c.wind_speed),
+                            Some(c.rh_min),
+                            None,
+                        ),
+                    )
+                })
+                .collect(),
+            ClimateAdjustment::StageAveraged { climatology } => {
+                let adjusted =
+                    self.with_stage_climate(&StageClimate::from_series(self, series, climatology));
+                series
+                    .iter()
+                    .map(|c| {
+                        let days_since_planting =
+                            c.date.signed_duration_since(self.planting_date).num_days();
+                        let growth_stage = adjusted.determine_growth_stage(days_since_planting);
+                        (
+                            c.date,
+                            adjusted.tabular_kc(days_since_planting, growth_stage),
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // Kc from the stage table before any climate adjustment, constant in the Initial and Mid stages and linearly
+    // interpolated in the Development and Late stages.
+    fn tabular_kc(&self, days_since_planting: i64, growth_stage: GrowthStage) -> f32 {
+        match growth_stage {
             GrowthStage::Initial => self.initial_end_kc.kc,
             GrowthStage::Development => {
                 let days_into = days_since_planting - (self.initial_end_kc.days as i64);
@@ -161,21 +531,37 @@ impl CropCoefficientsGs {
                 if length == 0 {
                     self.late_end_kc.kc
                 } else {
+                    // Kc stays at the end value after the late stage
                     self.mid_end_kc.kc
                         + (self.late_end_kc.kc - self.mid_end_kc.kc)
-                            * (days_into as f32 / length as f32)
+                            * (days_into.min(length) as f32 / length as f32)
                 }
             }
-        };
-
-        if matches!(growth_stage, GrowthStage::Mid | GrowthStage::Late) {
-            let wind_speed = wind_speed.unwrap_or(2.0);
-            let rh_min = rh_min.unwrap_or(45.0);
-            let crop_height = crop_height.unwrap_or(0.4);
-            kc = adjust_kc(kc, wind_speed, rh_min, crop_height);
         }
+    }
+
+    /// Returns the crop height in meters for the given number of days since planting. The crop grows linearly from
+    /// emergence height at planting to `crop_height` at the end of the development stage and stays constant afterwards.
+    ///
+    /// # Parameters
+    ///
+    /// - `days_since_planting`: An `i64` representing the number of days since the planting date.
+    ///
+    /// # Returns
+    ///
+    /// The crop height in meters as a `f32`.
+    pub fn crop_height_at(&self, days_since_planting: i64) -> f32 {
+        let progress = if self.development_end_kc.days > 0 {
+            days_since_planting as f32 / self.development_end_kc.days as f32
+        } else {
+            1.0
+        };
+        crop_height_profile(progress, self.crop_height as f32)
+    }
 
-        kc
+    /// Returns the crop height in meters on the given date, see `crop_height_at`.
+    pub fn crop_height_on(&self, date: NaiveDate) -> f32 {
+        self.crop_height_at(date.signed_duration_since(self.planting_date).num_days())
     }
 
     fn determine_growth_stage(&self, days_since_planting: i64) -> GrowthStage {
@@ -194,6 +580,8 @@ impl CropCoefficientsGs {
 
 /// Calculates the crop coefficient (Kc) based on the length of each growth stage in days and other optional environmental factors.
 /// It will adjust the Kc for wind speed, relative humidity, and crop height if provided the optional environmental factors.
+/// The Kc of the initial stage is rounded to 2 decimals, see `crop_coefficient_gs_result` for the Kc with the breakdown and
+/// the adjustment warnings.
 ///
 /// # Parameters
 ///
@@ -202,7 +590,9 @@ impl CropCoefficientsGs {
 /// - `cc`: A `CropCoefficients` struct containing the crop coefficients for different growth stages.
 /// - `wind_speed`: An `Option<f32>` representing the wind speed in m/s. If not provided, defaults to 2.0 m/s.
 /// - `rh_min`: An `Option<f32>` representing the minimum relative humidity in percentage. If not provided, defaults to 45.0%.
-/// - `crop_height`: An `Option<f32>` representing the crop height in meters. If not provided, defaults to 0.0 m.
+///   A humidity given as a fraction is not converted, use `crop_coefficient_gs_with_unit` for it.
+/// - `crop_height`: An `Option<f32>` representing a measured crop height in meters. If not provided, the height is taken from
+///   the crop height profile of `cc` (see `CropCoefficientsGs::crop_height_at`).
 ///
 /// # Returns
 ///
@@ -215,112 +605,408 @@ pub fn crop_coefficient_gs(
     rh_min: Option<f32>,
     crop_height: Option<f32>,
 ) -> (String, f32) {
-    let wind_speed = wind_speed.unwrap_or(2.0);
-    let mut rh_min = rh_min.unwrap_or(45.0);
-    let crop_height = crop_height.unwrap_or(1.391);
-
-    let days_since_planting = date.signed_duration_since(planting_date).num_days() as u16;
-
-    if rh_min < 1.0 {
-        rh_min *= 100.0; // Convert to percentage
-    }
-
-    if days_since_planting <= cc.initial_end_kc.days {
-        (cc.crop_name, (cc.initial_end_kc.kc * 100.0).round() / 100.0) // Kc for initial stage
-    } else if days_since_planting <= cc.development_end_kc.days {
-        // Interpolation between initial and development stages
-        (
-            cc.crop_name,
-            ((cc.initial_end_kc.kc
-                + (cc.development_end_kc.kc - cc.initial_end_kc.kc)
-                    * ((days_since_planting - cc.initial_end_kc.days)
-                        / (cc.development_end_kc.days - cc.initial_end_kc.days))
-                        as f32)
-                * 100.0)
-                / 100.0,
-        ) // Kc for development stage
-    } else if days_since_planting <= cc.mid_end_kc.days {
-        // Interpolation between development and mid-season stages
-        let kc_org = cc.development_end_kc.kc
-            + (cc.mid_end_kc.kc - cc.development_end_kc.kc)
-                * ((days_since_planting - cc.development_end_kc.days)
-                    / (cc.mid_end_kc.days - cc.development_end_kc.days)) as f32;
-        // Adjust Kc based on crop height and wind speed to compensate for arid and windy conditions
-        (
-            cc.crop_name,
-            (adjust_kc(kc_org, wind_speed, rh_min, crop_height) * 100.0) / 100.0,
-        ) // Kc for mid-season stage
-    } else {
-        // Interpolation between mid-season and end stages
-        let kc_org = cc.mid_end_kc.kc
-            - (cc.mid_end_kc.kc - cc.late_end_kc.kc)
-                * ((days_since_planting - cc.late_end_kc.days)
-                    / (cc.mid_end_kc.days - cc.late_end_kc.days)) as f32;
-        // let kc_org = (cc.development_end_kc.1 - ((cumulative_gdd - cc.mid_end_kc.0) / cc.late_end_kc.0)).max(cc.late_end_kc.1);
-        // Adjust Kc based on crop height and wind speed if it's larger than 0.45
-        if kc_org > 0.45 {
-            (
-                cc.crop_name,
-                (adjust_kc(kc_org, wind_speed, rh_min, crop_height) * 100.0) / 100.0,
-            ) // Kc for end stage
-        } else {
-            (cc.crop_name, (kc_org * 100.0) / 100.0) // Kc for end stage with default adjustment if Kc is less than 0.45
-        }
-    }
+    crop_coefficient_gs_with_unit(
+        planting_date,
+        date,
+        cc,
+        wind_speed,
+        rh_min,
+        HumidityUnit::Percent,
+        crop_height,
+    )
+}
+
+/// Calculates the crop coefficient (Kc) like `crop_coefficient_gs`, with the minimum relative humidity in the given unit.
+///
+/// # Parameters
+///
+/// - `planting_date`: A `NaiveDate` representing the planting date, which is used to determine the crop growth stage.
+/// - `date`: A `NaiveDate` representing the current date requested for the crop coefficient.
+/// - `cc`: A `CropCoefficientsGs` struct containing the crop coefficients for different growth stages.
+/// - `wind_speed`: An `Option<f32>` representing the wind speed in m/s. If not provided, defaults to 2.0 m/s.
+/// - `rh_min`: An `Option<f32>` representing the minimum relative humidity in `humidity_unit`. If not provided, defaults to 45%.
+/// - `humidity_unit`: The `HumidityUnit` of `rh_min`.
+/// - `crop_height`: An `Option<f32>` representing a measured crop height in meters. If not provided, the height is taken from
+///   the crop height profile of `cc`.
+///
+/// # Returns
+///
+/// A `(String, f32)` representing the name of the crop and the calculated crop coefficient (Kc) adjusted if given environmental conditions.
+pub fn crop_coefficient_gs_with_unit(
+    planting_date: NaiveDate,
+    date: NaiveDate,
+    cc: CropCoefficientsGs,
+    wind_speed: Option<f32>,
+    rh_min: Option<f32>,
+    humidity_unit: HumidityUnit,
+    crop_height: Option<f32>,
+) -> (String, f32) {
+    let result = crop_coefficient_gs_result(
+        planting_date,
+        date,
+        &cc,
+        wind_speed,
+        rh_min,
+        humidity_unit,
+        crop_height,
+    );
+    let kc = match result.growth_stage {
+        // Kc for the initial stage rounded to 2 decimals
+        GrowthStage::Initial => (result.tabular_kc * 100.0).round() / 100.0,
+        _ => result.kc,
+    };
+    (result.crop_name, kc)
+}
+
+/// Calculates the crop coefficient (Kc) like `crop_coefficient_gs`, returning a `KcResult` with the growth stage, the
+/// tabular Kc, the wind and humidity adjustment terms and the inputs used, including which were defaults.
+///
+/// # Parameters
+///
+/// - `planting_date`: A `NaiveDate` representing the planting date, which is used to determine the crop growth stage.
+/// - `date`: A `NaiveDate` representing the current date requested for the crop coefficient.
+/// - `cc`: A `CropCoefficientsGs` struct containing the crop coefficients for different growth stages.
+/// - `wind_speed`: An `Option<f32>` representing the wind speed in m/s. If not provided, defaults to 2.0 m/s.
+/// - `rh_min`: An `Option<f32>` representing the minimum relative humidity in `humidity_unit`. If not provided, defaults to 45%.
+/// - `humidity_unit`: The `HumidityUnit` of `rh_min`. A value that does not fit the unit is flagged with an
+///   `AdjustmentWarning::AmbiguousHumidityUnit` in the result instead of being converted.
+/// - `crop_height`: An `Option<f32>` representing a measured crop height in meters. If not provided, the height is taken from
+///   the crop height profile of `cc`.
+///
+/// # Returns
+///
+/// The `KcResult` with the breakdown of the calculated Kc.
+pub fn crop_coefficient_gs_result(
+    planting_date: NaiveDate,
+    date: NaiveDate,
+    cc: &CropCoefficientsGs,
+    wind_speed: Option<f32>,
+    rh_min: Option<f32>,
+    humidity_unit: HumidityUnit,
+    crop_height: Option<f32>,
+) -> KcResult {
+    let days_since_planting = date.signed_duration_since(planting_date).num_days();
+    cc.kc_result_at(
+        days_since_planting,
+        wind_speed,
+        rh_min,
+        humidity_unit,
+        crop_height,
+    )
+}
+
+/// Loads the climatological mean wind speed and minimum relative humidity from the `[climate]` table of `fao56.toml`,
+/// for use as the fallback climate of `ClimateAdjustment::StageAveraged`.
+pub fn load_climate_means() -> Result<ClimateMeans, Box<dyn std::error::Error>> {
+    load_climate_means_from("fao56.toml")
+}
+
+/// Loads the climatological mean wind speed and minimum relative humidity of the `[climate]` table of a crop table file in
+/// the format of `fao56.toml`.
+///
+/// # Parameters
+///
+/// - `path`: The path of the TOML file.
+///
+/// # Returns
+///
+/// A `Result` with the `ClimateMeans` of the file, or an error if the file cannot be read or parsed.
+pub fn load_climate_means_from<P: AsRef<Path>>(
+    path: P,
+) -> Result<ClimateMeans, Box<dyn std::error::Error>> {
+    let toml_str = fs::read_to_string(path)?;
+    let crop_data: CropKcData = toml::from_str(&toml_str)?;
+
+    Ok(ClimateMeans::new(
+        crop_data.climate.u2 as f32,
+        crop_data.climate.rh_min as f32,
+    ))
 }
 
 pub fn load_crop_coefficients(
 ) -> Result<HashMap<String, CropCoefficientsGs>, Box<dyn std::error::Error>> {
+    load_crop_coefficients_from("fao56.toml")
+}
+
+/// Loads the crop coefficients of the `[crops]` table of a crop table file in the format of `fao56.toml`, each crop with its
+/// own stage lengths (see `load_crop_variants_from` for the regional variants). Crops without `growth_stages_days`, such as
+/// the crops of `fao56.toml` without stage lengths in FAO-56 Table 11, are not loaded, see
+/// `load_crop_coefficients_with_stages_from`.
+///
+/// Only the `[crops]` table is loaded, including the wetlands. The open water surfaces of the `[open_water]` table have no
+/// growth stages and cannot be a `CropCoefficientsGs`, so every land cover of the file, these crops and the open water, is
+/// loaded by `load_land_covers_from`.
+///
+/// # Parameters
+///
+/// - `path`: The path of the TOML file.
+///
+/// # Returns
+///
+/// A `Result` with a `HashMap` of the crop name to its `CropCoefficientsGs`, or an error if the file cannot be read or parsed.
+pub fn load_crop_coefficients_from<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<String, CropCoefficientsGs>, Box<dyn std::error::Error>> {
+    Ok(parse_crop_variants(path)?
+        .into_iter()
+        .map(|(name, mut variants)| (name, variants.swap_remove(0)))
+        .collect())
+}
+
+/// Loads a crop of `fao56.toml` with local stage lengths, see `load_crop_coefficients_with_stages_from`.
+pub fn load_crop_coefficients_with_stages(
+    name: &str,
+    growth_stages_days: [u16; 4],
+    planting_date: NaiveDate,
+) -> Result<CropCoefficientsGs, Box<dyn std::error::Error>> {
+    load_crop_coefficients_with_stages_from("fao56.toml", name, growth_stages_days, planting_date)
+}
+
+/// Loads a crop of the `[crops]` table of a crop table file with local stage lengths, for the crops without stage lengths in
+/// the table or to replace them with local observations.
+///
+/// # Parameters
+///
+/// - `path`: The path of the TOML file.
+/// - `name`: The name of the crop.
+/// - `growth_stages_days`: The lengths of the initial, development, mid-season and late season stages in days.
+/// - `planting_date`: The planting date, or the start of the season of perennial crops.
+///
+/// # Returns
+///
+/// A `Result` with the `CropCoefficientsGs` of the crop, or an error if the file cannot be read or parsed or the crop is not
+/// in the table.
+pub fn load_crop_coefficients_with_stages_from<P: AsRef<Path>>(
+    path: P,
+    name: &str,
+    growth_stages_days: [u16; 4],
+    planting_date: NaiveDate,
+) -> Result<CropCoefficientsGs, Box<dyn std::error::Error>> {
+    let toml_str = fs::read_to_string(path)?;
+    let crop_data: CropKcData = toml::from_str(&toml_str)?;
+    let crop = crop_data
+        .crops
+        .get(name)
+        .ok_or(format!("Crop {} not found in the crop table.", name))?;
+
+    let mut cc =
+        crop_coefficients_with_stages(crop, &growth_stages_days.map(i32::from), planting_date)?;
+    cc.region = None;
+    Ok(cc)
+}
+
+/// Loads the crop coefficients of `fao56.toml` with every regional stage length variant, see `load_crop_variants_from`.
+pub fn load_crop_variants(
+) -> Result<HashMap<String, Vec<CropCoefficientsGs>>, Box<dyn std::error::Error>> {
+    load_crop_variants_from("fao56.toml")
+}
+
+/// Loads the crop coefficients of the `[crops]` table of a crop table file with every regional stage length variant.
+///
+/// Each crop may list `variants`, tables with a `region` label, the `growth_stages_days` and the `planting_date` of the
+/// region (FAO-56 Table 11), which share the coefficients of the crop. The optional `region` of the crop labels its own
+/// stage lengths. Crops without their own `growth_stages_days` are not loaded.
+///
+/// # Parameters
+///
+/// - `path`: The path of the TOML file.
+///
+/// # Returns
+///
+/// A `Result` with a `HashMap` of the crop name to its `CropCoefficientsGs` variants, the stage lengths of the crop first
+/// and then its regional variants in file order, or an error if the file cannot be read or parsed.
+pub fn load_crop_variants_from<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<String, Vec<CropCoefficientsGs>>, Box<dyn std::error::Error>> {
+    parse_crop_variants(path)
+}
+
+/// Loads the crop coefficients of `fao56.toml` for a region, see `load_crop_coefficients_for_region_from`.
+pub fn load_crop_coefficients_for_region(
+    region: &str,
+) -> Result<HashMap<String, CropCoefficientsGs>, Box<dyn std::error::Error>> {
+    load_crop_coefficients_for_region_from("fao56.toml", region)
+}
+
+/// Loads the crop coefficients of the `[crops]` table of a crop table file with the stage lengths of a region, for example
+/// "Mediterranean" or "Great Plains". Only the crops with a variant for the region are loaded.
+///
+/// # Parameters
+///
+/// - `path`: The path of the TOML file.
+/// - `region`: The region or the whole region label, matched case insensitively (see `find_variant`).
+///
+/// # Returns
+///
+/// A `Result` with a `HashMap` of the crop name to its `CropCoefficientsGs` for the region, or an error if the file cannot
+/// be read or parsed or no crop has a variant for the region.
+pub fn load_crop_coefficients_for_region_from<P: AsRef<Path>>(
+    path: P,
+    region: &str,
+) -> Result<HashMap<String, CropCoefficientsGs>, Box<dyn std::error::Error>> {
+    let crops: HashMap<String, CropCoefficientsGs> = parse_crop_variants(path)?
+        .into_iter()
+        .filter_map(|(name, variants)| find_variant(&variants, region).map(|cc| (name, cc.clone())))
+        .collect();
+
+    if crops.is_empty() {
+        return Err(format!("No crop has stage lengths for the region '{}'.", region).into());
+    }
+    Ok(crops)
+}
+
+// Parses the crops of a crop table file, each with its own stage lengths first and then its regional variants.
+fn parse_crop_variants<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<String, Vec<CropCoefficientsGs>>, Box<dyn std::error::Error>> {
     // Read and parse the TOML file
-    let toml_str = fs::read_to_string("fao56.toml")?;
+    let toml_str = fs::read_to_string(path)?;
     let crop_data: CropKcData = toml::from_str(&toml_str)?;
 
-    // Convert the HashMap of crops into a HashMap<String, CropCoefficientsGs>
-    let result: HashMap<String, CropCoefficientsGs> = crop_data
+    crop_data
         .crops
-        .into_iter()
-        .map(|(_key, crop)| {
-            // Calculate cumulative days for each stage end
-            let initial_days = crop.growth_stages_days[0] as u16;
-            let development_days = (crop.growth_stages_days[0] + crop.growth_stages_days[1]) as u16;
-            let mid_days = (crop.growth_stages_days[0]
-                + crop.growth_stages_days[1]
-                + crop.growth_stages_days[2]) as u16;
-            let late_days = (crop.growth_stages_days[0]
-                + crop.growth_stages_days[1]
-                + crop.growth_stages_days[2]
-                + crop.growth_stages_days[3]) as u16;
-
-            // Use the new method to create the struct
-            let cc = CropCoefficientsGs::new(
-                crop.name.clone(),
-                (initial_days, crop.k_ini as f32),
-                (development_days, crop.k_mid as f32), // Using k_mid as end of development
-                (mid_days, crop.k_mid as f32),
-                (late_days, crop.k_end as f32),
-                crop.planting_date,
-                crop.height_m,
-            );
-            (crop.name, cc)
+        .into_values()
+        .filter(|crop| crop.growth_stages_days.is_some())
+        .map(|crop| {
+            let cc = crop_coefficients_from(&crop)?;
+            let mut variants = vec![cc.clone()];
+            for variant in &crop.variants {
+                let stage_days = stage_end_days(&crop.name, &variant.growth_stages_days)?;
+                let mut regional = cc.clone();
+                regional.initial_end_kc.days = stage_days[0];
+                regional.development_end_kc.days = stage_days[1];
+                regional.mid_end_kc.days = stage_days[2];
+                regional.late_end_kc.days = stage_days[3];
+                regional.planting_date = variant.planting_date;
+                regional.region = Some(variant.region.clone());
+                variants.push(regional);
+            }
+            Ok((crop.name, variants))
         })
-        .collect();
+        .collect()
+}
+
+// Cumulative days at the end of each stage from the lengths of the initial, development, mid and late stages.
+fn stage_end_days(
+    crop_name: &str,
+    stage_days: &[i32],
+) -> Result<[u16; 4], Box<dyn std::error::Error>> {
+    if stage_days.len() != 4 || stage_days.iter().any(|days| *days < 0) {
+        return Err(format!(
+            "Crop {} must have 4 growth stage lengths of 0 days or more.",
+            crop_name
+        )
+        .into());
+    }
+
+    let mut end_days = [0; 4];
+    let mut total = 0;
+    for (end, days) in end_days.iter_mut().zip(stage_days) {
+        total += *days as u16;
+        *end = total;
+    }
+    Ok(end_days)
+}
+
+// Crop coefficients of a crop of the crop table with its own stage lengths.
+fn crop_coefficients_from(crop: &Crop) -> Result<CropCoefficientsGs, Box<dyn std::error::Error>> {
+    match (&crop.growth_stages_days, crop.planting_date) {
+        (Some(stage_days), Some(planting_date)) => {
+            crop_coefficients_with_stages(crop, stage_days, planting_date)
+        }
+        _ => Err(format!(
+            "Crop {} must have both growth_stages_days and planting_date.",
+            crop.name
+        )
+        .into()),
+    }
+}
+
+// Crop coefficients of a crop of the crop table with the given stage lengths and planting date.
+fn crop_coefficients_with_stages(
+    crop: &Crop,
+    stage_days: &[i32],
+    planting_date: NaiveDate,
+) -> Result<CropCoefficientsGs, Box<dyn std::error::Error>> {
+    let stage_days = stage_end_days(&crop.name, stage_days)?;
+
+    // Use the new method to create the struct
+    let mut cc = CropCoefficientsGs::new(
+        crop.name.clone(),
+        (stage_days[0], crop.k_ini as f32),
+        (stage_days[1], crop.k_mid as f32), // Using k_mid as end of development
+        (stage_days[2], crop.k_mid as f32),
+        (stage_days[3], crop.k_end as f32),
+        planting_date,
+        crop.height_m,
+    );
+    cc.region = crop.region.clone();
 
-    Ok(result)
+    if let Some(ky) = crop.ky {
+        let stages = match &crop.ky_stages {
+            Some(stages) => {
+                let stages: [f64; 4] = stages
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| format!("Crop {} must have 4 ky_stages.", crop.name))?;
+                Some(stages.map(|ky| ky as f32))
+            }
+            None => None,
+        };
+        cc = cc.with_yield_response(YieldResponse::new(ky as f32, stages));
+    }
+    match (crop.ece_threshold, crop.ece_slope) {
+        (Some(threshold), Some(slope)) => {
+            cc = cc.with_salinity_tolerance(SalinityTolerance::new(threshold as f32, slope as f32))
+        }
+        (None, None) => {}
+        _ => {
+            return Err(format!(
+                "Crop {} must have both ece_threshold and ece_slope.",
+                crop.name
+            )
+            .into())
+        }
+    }
+    match (crop.kcb_ini, crop.kcb_mid, crop.kcb_end) {
+        (Some(ini), Some(mid), Some(end)) => {
+            cc = cc.with_basal_kc(BasalKc::new(ini as f32, mid as f32, end as f32))
+        }
+        (None, None, None) => {}
+        _ => {
+            return Err(format!(
+                "Crop {} must have all of kcb_ini, kcb_mid and kcb_end.",
+                crop.name
+            )
+            .into())
+        }
+    }
+    if let Some(root_depth) = &crop.root_depth_m {
+        let [min, max]: [f64; 2] = root_depth
+            .as_slice()
+            .try_into()
+            .map_err(|_| format!("Crop {} must have a [min, max] root_depth_m.", crop.name))?;
+        cc = cc.with_root_depth(RootDepth::new(min as f32, max as f32));
+    }
+    if let Some(p) = crop.depletion_fraction {
+        cc = cc.with_depletion_fraction(p as f32);
+    }
+
+    Ok(cc)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kc_adjustment::AdjustmentWarning;
 
     #[test]
     fn test_load_crop_coefficients() {
-        let result = load_crop_coefficients();
-        if result.is_err() {
-            panic!("Error loading crop coefficients: {}", result.unwrap_err())
-        }
-
-        let crop_coefficients = result.unwrap();
-        assert_eq!(crop_coefficients.len(), 12);
+        let crop_coefficients = match load_crop_coefficients() {
+            Ok(crop_coefficients) => crop_coefficients,
+            Err(e) => panic!("Error loading crop coefficients: {}", e),
+        };
+        assert_eq!(crop_coefficients.len(), 91);
 
         // find a corn crop and check its coefficients
         let corn_coefficient = crop_coefficients.get("corn").expect("Corn not found");
@@ -333,4 +1019,413 @@ mod tests {
         assert_eq!(corn_coefficient.late_end_kc.days, 120);
         assert_eq!(corn_coefficient.late_end_kc.kc, 0.60);
     }
+
+    #[test]
+    fn test_load_crop_coefficients_with_stages() {
+        // Mint has coefficients but no stage lengths in the table
+        assert!(!load_crop_coefficients().unwrap().contains_key("mint"));
+
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 1).unwrap();
+        let mint =
+            load_crop_coefficients_with_stages("mint", [10, 30, 90, 20], planting_date).unwrap();
+        assert_eq!(mint.planting_date, planting_date);
+        assert_eq!(mint.region, None);
+        assert_eq!(mint.initial_end_kc.days, 10);
+        assert_eq!(mint.initial_end_kc.kc, 0.60);
+        assert_eq!(mint.mid_end_kc.days, 130);
+        assert_eq!(mint.mid_end_kc.kc, 1.15);
+        assert_eq!(mint.late_end_kc.days, 150);
+        assert_eq!(mint.late_end_kc.kc, 1.10);
+
+        // Local lengths replace the lengths of the table
+        let corn =
+            load_crop_coefficients_with_stages("corn", [25, 40, 45, 30], planting_date).unwrap();
+        assert_eq!(corn.late_end_kc.days, 140);
+
+        let unknown = load_crop_coefficients_with_stages("kale", [10, 30, 90, 20], planting_date);
+        assert!(unknown.unwrap_err().to_string().contains("kale"));
+    }
+
+    #[test]
+    fn test_crop_height_grows_through_development() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        );
+
+        assert!((cc.crop_height_at(0) - 0.05).abs() < 0.001);
+        assert!((cc.crop_height_at(25) - 1.025).abs() < 0.001);
+        assert!((cc.crop_height_at(50) - 2.0).abs() < 0.001);
+        assert!(
+            (cc.crop_height_on(NaiveDate::from_ymd_opt(2023, 8, 1).unwrap()) - 2.0).abs() < 0.001
+        );
+    }
+
+    #[test]
+    fn test_coefficient_from_date_uses_crop_height() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            3.0,
+        );
+        let mid_season = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+
+        // (0.04 * (4 - 2)) * (3 / 3)^0.3 = 0.08 with the profile height of 3 m
+        let kc = cc.coefficient_from_date(mid_season, Some(4.0), Some(45.0), None);
+        assert!((kc - 1.28).abs() < 0.001);
+
+        // A measured height overrides the profile
+        let kc = cc.coefficient_from_date(mid_season, Some(4.0), Some(45.0), Some(0.3));
+        assert!((kc - (1.2 + 0.08 * 0.1_f32.powf(0.3))).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_crop_coefficient_gs_interpolates_within_stages() {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 20).unwrap();
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            planting_date,
+            2.0,
+        );
+        let kc_on = |days: i64| {
+            let date = planting_date + chrono::Duration::days(days);
+            crop_coefficient_gs(planting_date, date, cc.clone(), None, None, None).1
+        };
+
+        // Halfway through the development and late stages
+        assert!((kc_on(35) - 0.75).abs() < 0.001);
+        assert!((kc_on(110) - 0.9).abs() < 0.001);
+        assert!((kc_on(115) - 0.75).abs() < 0.001);
+        // Kc stays at the end value after the season
+        assert!((kc_on(130) - 0.6).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_load_climate_means() {
+        let climate = load_climate_means().expect("Error loading climate means");
+        assert_eq!(climate, ClimateMeans::new(2.0, 45.0));
+    }
+
+    #[test]
+    fn test_coefficients_for_series_stage_averaged() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            3.0,
+        );
+        // Alternating windy and calm days through the mid and late stages, averaging 3 m/s
+        let series: Vec<DailyClimate> = (51..=120)
+            .map(|day| {
+                let wind_speed = if day % 2 == 0 { 4.0 } else { 2.0 };
+                DailyClimate::new(
+                    cc.planting_date + chrono::Duration::days(day),
+                    wind_speed,
+                    45.0,
+                )
+            })
+            .collect();
+
+        let daily = cc.coefficients_for_series(&series, ClimateAdjustment::Daily);
+        let averaged = cc.coefficients_for_series(
+            &series,
+            ClimateAdjustment::StageAveraged {
+                climatology: ClimateMeans::default(),
+            },
+        );
+
+        // Daily Kc jitters with the wind, the stage-averaged Kc_mid is adjusted by 0.04 * (3 - 2)
+        assert!((daily[9].1 - 1.28).abs() < 0.001);
+        assert!((daily[10].1 - 1.2).abs() < 0.001);
+        assert!((averaged[9].1 - 1.24).abs() < 0.001);
+        assert!((averaged[10].1 - 1.24).abs() < 0.001);
+
+        // Kc_end is adjusted with the late stage mean
+        let (last_date, last_kc) = averaged[averaged.len() - 1];
+        assert_eq!(last_date, NaiveDate::from_ymd_opt(2023, 8, 18).unwrap());
+        assert!((last_kc - 0.64).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_kc_result_from_date() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            3.0,
+        );
+
+        // 10 days into the 20 day late stage
+        let result = cc.kc_result_from_date(
+            NaiveDate::from_ymd_opt(2023, 8, 8).unwrap(),
+            Some(4.0),
+            None,
+            None,
+        );
+
+        assert_eq!(result.crop_name, "corn");
+        assert_eq!(result.growth_stage, GrowthStage::Late);
+        assert!((result.stage_fraction - 0.5).abs() < 0.001);
+        assert!((result.tabular_kc - 0.9).abs() < 0.001);
+        assert!((result.wind_term - 0.08).abs() < 0.001);
+        assert_eq!(result.humidity_term, 0.0);
+        assert!(!result.wind_speed.is_default);
+        assert!(result.rh_min.is_default);
+        assert!(result.crop_height.is_default);
+        assert!((result.crop_height.value - 3.0).abs() < 0.001);
+        assert!((result.kc - 0.98).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_late_stage_kc_below_045_is_not_adjusted() {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 20).unwrap();
+        let cc = CropCoefficientsGs::new(
+            "barley".to_string(),
+            (20, 0.3),
+            (50, 1.15),
+            (100, 1.15),
+            (120, 0.25),
+            planting_date,
+            1.0,
+        );
+
+        // Last day of the late stage at Kc_end, windy and dry
+        let date = planting_date + chrono::Duration::days(120);
+        let (_, kc) =
+            crop_coefficient_gs(planting_date, date, cc.clone(), Some(5.0), Some(20.0), None);
+        assert!((kc - 0.25).abs() < 0.0001);
+
+        // The Kc by date keeps the tabular Kc_end as well
+        let result = cc.kc_result_from_date(date, Some(5.0), Some(20.0), None);
+        assert_eq!(result.growth_stage, GrowthStage::Late);
+        assert!((result.tabular_kc - 0.25).abs() < 0.0001);
+        assert_eq!(result.adjustment(), 0.0);
+        assert!((result.kc - 0.25).abs() < 0.0001);
+        assert!(
+            (cc.coefficient_from_date(date, Some(5.0), Some(20.0), None) - 0.25).abs() < 0.0001
+        );
+
+        // Earlier in the late stage the Kc is above 0.45 and adjusted
+        let result = cc.kc_result_from_date(
+            planting_date + chrono::Duration::days(105),
+            Some(5.0),
+            Some(20.0),
+            None,
+        );
+        assert!((result.tabular_kc - 0.925).abs() < 0.0001);
+        assert!(result.adjustment() > 0.0);
+    }
+
+    #[test]
+    fn test_crop_coefficient_gs_result_flags_humidity_unit() {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 20).unwrap();
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            planting_date,
+            2.0,
+        );
+        let date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+
+        // A fraction declared as percent is flagged, not converted
+        let result = crop_coefficient_gs_result(
+            planting_date,
+            date,
+            &cc,
+            None,
+            Some(0.3),
+            HumidityUnit::Percent,
+            None,
+        );
+        assert_eq!(
+            result.warnings[0],
+            AdjustmentWarning::AmbiguousHumidityUnit {
+                value: 0.3,
+                unit: HumidityUnit::Percent
+            }
+        );
+
+        let fraction = crop_coefficient_gs_result(
+            planting_date,
+            date,
+            &cc,
+            None,
+            Some(0.3),
+            HumidityUnit::Fraction,
+            None,
+        );
+        let percent = crop_coefficient_gs_result(
+            planting_date,
+            date,
+            &cc,
+            None,
+            Some(30.0),
+            HumidityUnit::Percent,
+            None,
+        );
+        assert!(fraction.warnings.is_empty());
+        assert!(fraction.humidity_term > 0.0);
+        assert_eq!(fraction.kc, percent.kc);
+    }
+
+    #[test]
+    fn test_initial_stage_kc_is_rounded() {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 20).unwrap();
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.333),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            planting_date,
+            2.0,
+        );
+        let (_, kc) =
+            crop_coefficient_gs(planting_date, planting_date, cc.clone(), None, None, None);
+        assert_eq!(kc, 0.33);
+
+        // The Kc by date is not rounded
+        assert_eq!(
+            cc.coefficient_from_date(planting_date, None, None, None),
+            0.333
+        );
+    }
+
+    #[test]
+    fn test_crop_coefficient_gs_late_stage() {
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 20).unwrap();
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            planting_date,
+            2.0,
+        );
+
+        let (name, kc) = crop_coefficient_gs_with_unit(
+            planting_date,
+            NaiveDate::from_ymd_opt(2023, 8, 8).unwrap(),
+            cc,
+            None,
+            Some(0.45),
+            HumidityUnit::Fraction,
+            None,
+        );
+
+        assert_eq!(name, "corn");
+        assert!((kc - 0.9).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_growth_stage_display_and_from_str() {
+        for stage in GrowthStage::ALL {
+            assert_eq!(stage.to_string().parse::<GrowthStage>(), Ok(stage));
+        }
+        assert_eq!("Mid-Season".parse::<GrowthStage>(), Ok(GrowthStage::Mid));
+        assert_eq!("late_season".parse::<GrowthStage>(), Ok(GrowthStage::Late));
+        assert!("harvest".parse::<GrowthStage>().is_err());
+        assert!(GrowthStage::Initial < GrowthStage::Development);
+        assert!(GrowthStage::Mid < GrowthStage::Late);
+    }
+
+    #[test]
+    fn test_growth_stage_serde() {
+        #[derive(Serialize, Deserialize)]
+        struct Row {
+            stage: GrowthStage,
+        }
+
+        let row: Row = toml::from_str("stage = \"development\"").unwrap();
+        assert_eq!(row.stage, GrowthStage::Development);
+        assert_eq!(
+            toml::to_string(&Row {
+                stage: GrowthStage::Late
+            })
+            .unwrap()
+            .trim(),
+            "stage = \"late\""
+        );
+    }
+
+    #[test]
+    fn test_stage_timeline() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        );
+
+        let timeline = cc.stage_timeline();
+
+        assert_eq!(timeline.len(), 4);
+        assert_eq!(
+            timeline[0].start_date,
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap()
+        );
+        assert_eq!(
+            timeline[0].end_date,
+            NaiveDate::from_ymd_opt(2023, 5, 10).unwrap()
+        );
+        assert_eq!(timeline[1].stage, GrowthStage::Development);
+        assert_eq!(
+            timeline[1].start_date,
+            NaiveDate::from_ymd_opt(2023, 5, 11).unwrap()
+        );
+        assert_eq!(timeline[1].days, 30);
+        assert_eq!(
+            timeline[3].end_date,
+            NaiveDate::from_ymd_opt(2023, 8, 18).unwrap()
+        );
+        for period in &timeline {
+            assert_eq!(cc.growth_stage_on(period.start_date), period.stage);
+            assert_eq!(cc.growth_stage_on(period.end_date), period.stage);
+        }
+    }
+
+    #[test]
+    fn test_stage_progress_on() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        );
+
+        let progress = cc.stage_progress_on(NaiveDate::from_ymd_opt(2023, 6, 24).unwrap());
+
+        assert_eq!(progress.stage, GrowthStage::Mid);
+        assert!((progress.fraction - 0.3).abs() < 0.001);
+        assert_eq!(progress.days_remaining, 35);
+    }
 }
@@ -0,0 +1,279 @@
+use crate::kc_model::KcModel;
+use crate::kc_result::KcResult;
+use crate::kcc_gs::{load_crop_coefficients_from, CropCoefficientsGs, GrowthStage};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::Path;
+
+// Kc of shallow open water and of deep water over the year (FAO-56 Table 12). Deep water stores heat in spring and summer
+// and releases it in fall and winter, so its Kc swings between 0.65 and 1.25 around the mean.
+const SHALLOW_WATER_KC: f32 = 1.05;
+const DEEP_WATER_MEAN_KC: f32 = 0.95;
+const DEEP_WATER_KC_AMPLITUDE: f32 = 0.30;
+const SHALLOW_WATER_DEPTH: f32 = 2.0;
+const DEEP_WATER_DEPTH: f32 = 5.0;
+
+// Day of the year of the highest Kc of deep water in the Northern Hemisphere, late October.
+pub const DEEP_WATER_PEAK_DAY: u16 = 300;
+
+// Open water surface with an evaporation coefficient Kc (E = Kc ETo) that depends on the depth. Water of 2 m or less has a
+// constant Kc, deeper water a seasonal Kc that lags the radiation through the heat stored in the water column, fully from 5 m.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenWater {
+    pub name: String,
+    pub depth: f32,
+    pub start_date: NaiveDate,
+    pub peak_day: u16,
+}
+
+impl OpenWater {
+    /// Creates a new `OpenWater` with the mean depth in meters and the start of the period of the Kc query, with the
+    /// Northern Hemisphere heat storage lag. Panics if the depth is negative.
+    pub fn new(name: String, depth: f32, start_date: NaiveDate) -> Self {
+        if depth < 0.0 {
+            panic!("Water depth cannot be negative.");
+        }
+
+        OpenWater {
+            name,
+            depth,
+            start_date,
+            peak_day: DEEP_WATER_PEAK_DAY,
+        }
+    }
+
+    /// Returns the open water with the day of the year of the highest Kc of deep water, such as 117 in the Southern
+    /// Hemisphere.
+    pub fn with_peak_day(mut self, peak_day: u16) -> Self {
+        self.peak_day = peak_day;
+        self
+    }
+
+    /// Returns the fraction (0 - 1) of the deep water heat storage effect at the depth.
+    pub fn heat_storage_fraction(&self) -> f32 {
+        ((self.depth - SHALLOW_WATER_DEPTH) / (DEEP_WATER_DEPTH - SHALLOW_WATER_DEPTH))
+            .clamp(0.0, 1.0)
+    }
+
+    /// Returns the evaporation coefficient on a date.
+    ///
+    /// This is an approximation, not an FAO-56 procedure. FAO-56 Table 12 only gives Kc 1.05 for water less than 2 m deep
+    /// and, for clear water more than 5 m deep in a temperate climate, Kc 0.65 in spring (April - May) and Kc 1.25 in
+    /// fall (October - November). Deep water follows a cosine through these two values, a mean of 0.95 and an amplitude
+    /// of 0.30 with the highest Kc on `peak_day` (day 300, late October, by default). Between 2 and 5 m the mean and the
+    /// amplitude are blended linearly with the depth (see `heat_storage_fraction`), for which FAO-56 gives no values.
+    /// Calibrate against pan or lake evaporation data where the timing of the heat storage matters.
+    pub fn kc_on(&self, date: NaiveDate) -> f32 {
+        let fraction = self.heat_storage_fraction();
+        let mean = SHALLOW_WATER_KC + fraction * (DEEP_WATER_MEAN_KC - SHALLOW_WATER_KC);
+        let phase = 2.0 * PI * (date.ordinal() as f32 - self.peak_day as f32) / 365.0;
+        mean + fraction * DEEP_WATER_KC_AMPLITUDE * phase.cos()
+    }
+}
+
+impl KcModel for OpenWater {
+    fn crop_name(&self) -> &str {
+        &self.name
+    }
+
+    fn planting_date(&self) -> NaiveDate {
+        self.start_date
+    }
+
+    // Open water has no season
+    fn season_end(&self) -> Option<NaiveDate> {
+        None
+    }
+
+    fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        _wind_speed: Option<f32>,
+        _rh_min: Option<f32>,
+        _crop_height: Option<f32>,
+    ) -> f32 {
+        self.kc_on(date)
+    }
+}
+
+// Land cover of the crop table, a crop or natural vegetation with a growth stage Kc curve (including wetlands) or an open
+// water surface.
+#[derive(Debug, Clone)]
+pub enum LandCover {
+    Vegetation(CropCoefficientsGs),
+    OpenWater(OpenWater),
+}
+
+impl LandCover {
+    /// Returns the land cover as a `KcModel` for the Kc queries.
+    pub fn as_kc_model(&self) -> &dyn KcModel {
+        match self {
+            LandCover::Vegetation(cc) => cc,
+            LandCover::OpenWater(water) => water,
+        }
+    }
+}
+
+impl KcModel for LandCover {
+    fn crop_name(&self) -> &str {
+        self.as_kc_model().crop_name()
+    }
+
+    fn planting_date(&self) -> NaiveDate {
+        self.as_kc_model().planting_date()
+    }
+
+    fn season_end(&self) -> Option<NaiveDate> {
+        self.as_kc_model().season_end()
+    }
+
+    fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        self.as_kc_model()
+            .coefficient_from_date(date, wind_speed, rh_min, crop_height)
+    }
+
+    fn growth_stage_on(&self, date: NaiveDate) -> Option<GrowthStage> {
+        self.as_kc_model().growth_stage_on(date)
+    }
+
+    fn kc_result_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> Option<KcResult> {
+        self.as_kc_model()
+            .kc_result_from_date(date, wind_speed, rh_min, crop_height)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OpenWaterEntry {
+    name: String,
+    depth_m: f32,          // Mean depth in meters
+    start_date: NaiveDate, // Start of the period of the Kc query
+    peak_day: Option<u16>, // Day of the year of the highest Kc of deep water
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OpenWaterData {
+    #[serde(default)]
+    open_water: HashMap<String, OpenWaterEntry>,
+}
+
+/// Loads the land covers of `fao56.toml`, see `load_land_covers_from`.
+pub fn load_land_covers() -> Result<HashMap<String, LandCover>, Box<dyn std::error::Error>> {
+    load_land_covers_from("fao56.toml")
+}
+
+/// Loads every land cover of a crop table file: the crops and natural vegetation of the `[crops]` table (as
+/// `load_crop_coefficients_from`) and the open water surfaces of the `[open_water]` table. Each open water surface has a
+/// `name`, a mean depth in meters `depth_m`, a `start_date` and an optional `peak_day` of the heat storage lag.
+///
+/// Open water has no growth stages, so it is a separate table returned here rather than by `load_crop_coefficients_from`,
+/// whose callers rely on the stage lengths. Both are queried through `KcModel`.
+///
+/// # Parameters
+///
+/// - `path`: The path of the crop table file.
+///
+/// # Returns
+///
+/// A `HashMap<String, LandCover>` keyed by name, or an error if the file cannot be read or parsed or a name is used twice.
+pub fn load_land_covers_from<P: AsRef<Path>>(
+    path: P,
+) -> Result<HashMap<String, LandCover>, Box<dyn std::error::Error>> {
+    let mut result: HashMap<String, LandCover> = load_crop_coefficients_from(&path)?
+        .into_iter()
+        .map(|(name, cc)| (name, LandCover::Vegetation(cc)))
+        .collect();
+
+    let toml_str = fs::read_to_string(&path)?;
+    let water_data: OpenWaterData = toml::from_str(&toml_str)?;
+    for entry in water_data.open_water.into_values() {
+        if entry.depth_m < 0.0 {
+            return Err(format!("Open water {} has a negative depth.", entry.name).into());
+        }
+        let mut water = OpenWater::new(entry.name.clone(), entry.depth_m, entry.start_date);
+        if let Some(peak_day) = entry.peak_day {
+            water = water.with_peak_day(peak_day);
+        }
+        if result
+            .insert(entry.name.clone(), LandCover::OpenWater(water))
+            .is_some()
+        {
+            return Err(format!("Land cover {} is defined twice.", entry.name).into());
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_shallow_water_kc_is_constant() {
+        let pond = OpenWater::new("pond".to_string(), 1.5, date(1, 1));
+        assert_eq!(pond.heat_storage_fraction(), 0.0);
+        assert!((pond.kc_on(date(4, 27)) - 1.05).abs() < 0.0001);
+        assert!((pond.kc_on(date(10, 27)) - 1.05).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_deep_water_heat_storage_lag() {
+        let reservoir = OpenWater::new("reservoir".to_string(), 10.0, date(1, 1));
+
+        // Highest in late October, lowest half a year earlier
+        assert!((reservoir.kc_on(date(10, 27)) - 1.25).abs() < 0.0001);
+        assert!((reservoir.kc_on(date(4, 28)) - 0.65).abs() < 0.001);
+
+        // Halfway between shallow and deep water
+        let lake = OpenWater::new("lake".to_string(), 3.5, date(1, 1));
+        assert!((lake.kc_on(date(10, 27)) - 1.15).abs() < 0.0001);
+
+        let southern = reservoir.clone().with_peak_day(117);
+        assert!((southern.kc_on(date(4, 27)) - 1.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_load_land_covers() {
+        let land_covers = load_land_covers().unwrap();
+
+        let reservoir = &land_covers["reservoir"];
+        assert!(matches!(reservoir, LandCover::OpenWater(_)));
+        assert_eq!(reservoir.season_end(), None);
+        assert!(
+            (reservoir.coefficient_from_date(date(10, 27), None, None, None) - 1.25).abs() < 0.0001
+        );
+
+        // Wetlands use the growth stage curve
+        let cattails = &land_covers["cattails_bulrushes"];
+        assert!(matches!(cattails, LandCover::Vegetation(_)));
+        assert_eq!(cattails.crop_name(), "cattails_bulrushes");
+        assert!(
+            (cattails.coefficient_from_date(date(7, 1), None, None, None) - 1.2).abs() < 0.0001
+        );
+
+        // Every crop is a land cover, the open water is not a crop
+        let crops = load_crop_coefficients_from("fao56.toml").unwrap();
+        assert_eq!(land_covers.len(), crops.len() + 2);
+        assert!(crops.keys().all(|name| land_covers.contains_key(name)));
+        assert!(!crops.contains_key("reservoir"));
+    }
+}
@@ -1,10 +1,172 @@
+mod crop_table;
+mod district;
 mod gdd;
+mod ground_cover;
+mod growing_season;
+mod irrigation;
+mod kc_adjustment;
+mod kc_curve;
 mod kc_gdd;
+mod kc_ini;
+mod kc_model;
+mod kc_result;
 mod kcc_gs;
+mod land_cover;
+mod ndvi;
+mod output;
+mod phenology;
+mod salinity;
+mod season;
+mod soil;
+mod stage_climate;
+#[cfg(test)]
+mod test_fixtures;
+mod water_use;
+mod weather;
+mod weather_csv;
+mod yield_response;
 
+pub use crop_table::adjusted_depletion_fraction;
+pub use crop_table::find_variant;
+pub use crop_table::BasalKc;
+pub use crop_table::RootDepth;
+pub use district::district_water_use;
+pub use district::DistrictTotals;
+pub use district::DistrictWaterUse;
+pub use district::WaterAmount;
 pub use gdd::calculate_gdd;
+pub use ground_cover::effective_ground_cover;
+pub use ground_cover::kc_from_ground_cover;
+pub use ground_cover::kcb_full;
+pub use ground_cover::GroundCover;
+pub use ground_cover::KC_MIN;
+pub use growing_season::growing_seasons;
+pub use growing_season::GrowingSeason;
+pub use growing_season::GrowingSeasonClimatology;
+pub use growing_season::GrowingSeasonOptions;
+pub use irrigation::schedule_irrigation;
+pub use irrigation::total_available_water;
+pub use irrigation::water_stress_coefficient;
+pub use irrigation::DepletionDay;
+pub use irrigation::IrrigationEvent;
+pub use irrigation::IrrigationSchedule;
+pub use irrigation::IrrigationSystem;
+pub use irrigation::ScheduleOptions;
+pub use kc_adjustment::adjust_kc_checked;
+pub use kc_adjustment::AdjustmentWarning;
+pub use kc_adjustment::HumidityUnit;
+pub use kc_adjustment::KcAdjustment;
+pub use kc_curve::load_kc_curves;
+pub use kc_curve::load_kc_curves_from;
+pub use kc_curve::CurveAxis;
+pub use kc_curve::Interpolation;
+pub use kc_curve::KcCurve;
+pub use kc_gdd::corn_gdd_to_maturity;
 pub use kc_gdd::crop_coefficient_gdd;
+pub use kc_gdd::crop_coefficient_gdd_result;
+pub use kc_gdd::crop_coefficient_gdd_with_unit;
 pub use kc_gdd::CropCoefficientsGdd;
+pub use kc_gdd::CropCoefficientsGddNormalized;
+pub use kc_ini::kc_ini;
+pub use kc_ini::kc_ini_heavy_infiltration;
+pub use kc_ini::kc_ini_light_infiltration;
+pub use kc_ini::InitialWetting;
+pub use kc_ini::IrrigationType;
+pub use kc_ini::SoilTexture;
+pub use kc_model::GddKcModel;
+pub use kc_model::KcModel;
+pub use kc_result::KcInput;
+pub use kc_result::KcResult;
 pub use kcc_gs::crop_coefficient_gs;
+pub use kcc_gs::crop_coefficient_gs_result;
+pub use kcc_gs::crop_coefficient_gs_with_unit;
+pub use kcc_gs::load_climate_means;
+pub use kcc_gs::load_climate_means_from;
 pub use kcc_gs::load_crop_coefficients;
+pub use kcc_gs::load_crop_coefficients_for_region;
+pub use kcc_gs::load_crop_coefficients_for_region_from;
+pub use kcc_gs::load_crop_coefficients_from;
+pub use kcc_gs::load_crop_coefficients_with_stages;
+pub use kcc_gs::load_crop_coefficients_with_stages_from;
+pub use kcc_gs::load_crop_variants;
+pub use kcc_gs::load_crop_variants_from;
 pub use kcc_gs::CropCoefficientsGs;
+pub use kcc_gs::GrowthStage;
+pub use kcc_gs::KcStage;
+pub use kcc_gs::ParseGrowthStageError;
+pub use kcc_gs::StagePeriod;
+pub use kcc_gs::StageProgress;
+pub use land_cover::load_land_covers;
+pub use land_cover::load_land_covers_from;
+pub use land_cover::LandCover;
+pub use land_cover::OpenWater;
+pub use land_cover::DEEP_WATER_PEAK_DAY;
+pub use ndvi::ndvi;
+pub use ndvi::savi;
+pub use ndvi::IndexObservation;
+pub use ndvi::KcbRelation;
+pub use ndvi::NdviKcModel;
+pub use output::write_csv;
+pub use output::write_json_lines;
+pub use output::write_metadata_sidecar;
+pub use output::write_parquet;
+pub use output::write_records;
+pub use output::DailyRecord;
+pub use output::OutputFormat;
+pub use output::OutputMetadata;
+pub use output::SCHEMA_VERSION;
+pub use phenology::CornPhenology;
+pub use phenology::CornStage;
+pub use phenology::ParseStageError;
+pub use phenology::SoybeanDevelopment;
+pub use phenology::SoybeanPhenology;
+pub use phenology::SoybeanStage;
+pub use phenology::StageDate;
+pub use salinity::salinity_adjusted_etc;
+pub use salinity::salinity_stress_coefficient;
+pub use salinity::SalinityAdjustedEtc;
+pub use salinity::SalinityTolerance;
+pub use season::residue_kc;
+pub use season::simulate_season;
+pub use season::Season;
+pub use season::SeasonDay;
+pub use season::SeasonOptions;
+pub use season::Termination;
+pub use season::TerminationCause;
+pub use soil::load_soil_profiles_csv;
+pub use soil::read_soil_profiles_csv;
+pub use soil::ParseSoilTextureClassError;
+pub use soil::RootZoneWater;
+pub use soil::SoilLayer;
+pub use soil::SoilProfile;
+pub use soil::SoilProperties;
+pub use soil::SoilTextureClass;
+pub use stage_climate::ClimateAdjustment;
+pub use stage_climate::ClimateMeans;
+pub use stage_climate::DailyClimate;
+pub use stage_climate::StageClimate;
+pub use water_use::seasonal_water_use;
+pub use water_use::EffectivePrecipitation;
+pub use water_use::MonthlyWaterUse;
+pub use water_use::SeasonalWaterUse;
+pub use weather::actual_vapor_pressure;
+pub use weather::day_length;
+pub use weather::rh_min_from_dew_point;
+pub use weather::saturation_vapor_pressure;
+pub use weather::wind_speed_at_2m;
+pub use weather::WeatherRecord;
+pub use weather_csv::load_weather_csv;
+pub use weather_csv::read_weather_csv;
+pub use weather_csv::DepthUnit;
+pub use weather_csv::GapFill;
+pub use weather_csv::QcFlag;
+pub use weather_csv::QcIssue;
+pub use weather_csv::SpeedUnit;
+pub use weather_csv::TemperatureUnit;
+pub use weather_csv::WeatherCsvLayout;
+pub use weather_csv::WeatherSeries;
+pub use yield_response::relative_yield_decrease;
+pub use yield_response::yield_response;
+pub use yield_response::StageYieldResponse;
+pub use yield_response::YieldImpact;
+pub use yield_response::YieldResponse;
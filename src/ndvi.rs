@@ -0,0 +1,355 @@
+use crate::kc_model::KcModel;
+use crate::kcc_gs::{CropCoefficientsGs, GrowthStage};
+use chrono::NaiveDate;
+
+/// Calculates the normalized difference vegetation index (NDVI) from the red and near infrared reflectance.
+pub fn ndvi(red: f32, nir: f32) -> f32 {
+    if nir + red <= 0.0 {
+        return 0.0;
+    }
+    (nir - red) / (nir + red)
+}
+
+/// Calculates the soil adjusted vegetation index (SAVI) from the red and near infrared reflectance with the soil brightness
+/// factor L, 0.5 for intermediate vegetation densities.
+pub fn savi(red: f32, nir: f32, l: f32) -> f32 {
+    if nir + red + l <= 0.0 {
+        return 0.0;
+    }
+    (1.0 + l) * (nir - red) / (nir + red + l)
+}
+
+// Vegetation index (NDVI or SAVI) of a field from a satellite image. The value is `None` when the field was masked as cloud
+// or cloud shadow.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexObservation {
+    pub date: NaiveDate,
+    pub value: Option<f32>,
+}
+
+impl IndexObservation {
+    /// Creates a new `IndexObservation` with a clear sky value.
+    pub fn new(date: NaiveDate, value: f32) -> Self {
+        IndexObservation {
+            date,
+            value: Some(value),
+        }
+    }
+
+    /// Creates a new `IndexObservation` for an image masked as cloud.
+    pub fn cloudy(date: NaiveDate) -> Self {
+        IndexObservation { date, value: None }
+    }
+}
+
+// Relation from a vegetation index to the basal crop coefficient (Kcb). `Linear` is Kcb = slope * VI + intercept, as in the
+// NDVI and SAVI relations fitted for corn and wheat. `Scaled` is the non-linear relation through the fractional cover,
+// Kcb = kcb_min + (kcb_max - kcb_min) * (1 - ((vi_max - VI) / (vi_max - vi_min))^exponent), between the index of bare soil
+// (vi_min) and full cover (vi_max).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KcbRelation {
+    Linear {
+        slope: f32,
+        intercept: f32,
+    },
+    Scaled {
+        vi_min: f32,
+        vi_max: f32,
+        kcb_min: f32,
+        kcb_max: f32,
+        exponent: f32,
+    },
+}
+
+impl KcbRelation {
+    /// Returns the Kcb of a vegetation index value, no less than 0.
+    pub fn kcb(&self, vi: f32) -> f32 {
+        let kcb = match self {
+            KcbRelation::Linear { slope, intercept } => slope * vi + intercept,
+            KcbRelation::Scaled {
+                vi_min,
+                vi_max,
+                kcb_min,
+                kcb_max,
+                exponent,
+            } => {
+                let range = vi_max - vi_min;
+                if range <= 0.0 {
+                    return *kcb_max;
+                }
+                let relative = ((vi_max - vi) / range).clamp(0.0, 1.0);
+                kcb_min + (kcb_max - kcb_min) * (1.0 - relative.powf(*exponent))
+            }
+        };
+        kcb.max(0.0)
+    }
+}
+
+// Kc model driven by a vegetation index time series. The index is interpolated linearly between the clear sky observations
+// to daily values, skipping the cloudy ones, and converted to Kcb with the `KcbRelation`. The Kc is Kcb plus the soil
+// evaporation coefficient Ke. With a fallback FAO-56 curve, the Kc blends from the reflectance Kc at an observation to the
+// curve at `blend_days` or more from the nearest observation, so long cloud gaps and days outside the imagery follow the curve.
+#[derive(Debug, Clone)]
+pub struct NdviKcModel {
+    pub crop_name: String,
+    pub planting_date: NaiveDate,
+    pub relation: KcbRelation,
+    pub evaporation_coefficient: f32,
+    pub fallback: Option<CropCoefficientsGs>,
+    pub blend_days: u16,
+    observations: Vec<(NaiveDate, f32)>,
+}
+
+impl NdviKcModel {
+    /// Creates a new `NdviKcModel` from the observations of a field in any order.
+    ///
+    /// # Parameters
+    ///
+    /// - `crop_name`: A `String` representing the name of the crop.
+    /// - `planting_date`: The planting date of the crop.
+    /// - `relation`: The `KcbRelation` of the vegetation index.
+    /// - `observations`: The `IndexObservation` series of the field, cloudy observations are skipped.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with the `NdviKcModel` without soil evaporation or fallback curve, or an error if no observation is clear.
+    pub fn new(
+        crop_name: String,
+        planting_date: NaiveDate,
+        relation: KcbRelation,
+        observations: &[IndexObservation],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut clear: Vec<(NaiveDate, f32)> = observations
+            .iter()
+            .filter_map(|o| o.value.map(|value| (o.date, value)))
+            .filter(|(_, value)| value.is_finite() && (-1.0..=1.0).contains(value))
+            .collect();
+        if clear.is_empty() {
+            return Err(format!(
+                "No clear vegetation index observation of {}, at least one is required.",
+                crop_name
+            )
+            .into());
+        }
+        clear.sort_by_key(|(date, _)| *date);
+        clear.dedup_by_key(|(date, _)| *date);
+
+        Ok(NdviKcModel {
+            crop_name,
+            planting_date,
+            relation,
+            evaporation_coefficient: 0.0,
+            fallback: None,
+            blend_days: 0,
+            observations: clear,
+        })
+    }
+
+    /// Returns the model with the soil evaporation coefficient Ke added to Kcb.
+    pub fn with_evaporation_coefficient(mut self, ke: f32) -> Self {
+        self.evaporation_coefficient = ke.max(0.0);
+        self
+    }
+
+    /// Returns the model blending to the fallback FAO-56 curve over `blend_days` from the nearest observation.
+    pub fn with_fallback(mut self, fallback: CropCoefficientsGs, blend_days: u16) -> Self {
+        self.fallback = Some(fallback);
+        self.blend_days = blend_days;
+        self
+    }
+
+    /// Returns the clear sky observations in date order.
+    pub fn observations(&self) -> &[(NaiveDate, f32)] {
+        &self.observations
+    }
+
+    /// Returns the vegetation index on a date, interpolated linearly between the observations and held constant before the
+    /// first and after the last.
+    pub fn index_on(&self, date: NaiveDate) -> f32 {
+        let after = self.observations.partition_point(|(d, _)| *d < date);
+        if after == 0 {
+            return self.observations[0].1;
+        }
+        if after == self.observations.len() {
+            return self.observations[after - 1].1;
+        }
+
+        let (start_date, start) = self.observations[after - 1];
+        let (end_date, end) = self.observations[after];
+        let span = end_date.signed_duration_since(start_date).num_days() as f32;
+        let elapsed = date.signed_duration_since(start_date).num_days() as f32;
+        start + (end - start) * elapsed / span
+    }
+
+    /// Returns the basal crop coefficient (Kcb) from the vegetation index on a date.
+    pub fn kcb_on(&self, date: NaiveDate) -> f32 {
+        self.relation.kcb(self.index_on(date))
+    }
+
+    /// Returns the weight (0 - 1) of the reflectance Kc on a date, 1 on the day of an observation decreasing to 0 at
+    /// `blend_days` from the nearest observation. Always 1 without a fallback curve.
+    pub fn reflectance_weight(&self, date: NaiveDate) -> f32 {
+        if self.fallback.is_none() {
+            return 1.0;
+        }
+        let nearest = self
+            .observations
+            .iter()
+            .map(|(d, _)| date.signed_duration_since(*d).num_days().abs())
+            .min()
+            .unwrap();
+        if self.blend_days == 0 {
+            return if nearest == 0 { 1.0 } else { 0.0 };
+        }
+        (1.0 - nearest as f32 / self.blend_days as f32).max(0.0)
+    }
+
+    /// Calculates the crop coefficient (Kc) on a date, the reflectance Kcb plus Ke blended with the fallback curve. The
+    /// climate adjustment applies to the fallback curve only, the reflectance already reflects the canopy.
+    pub fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        let reflectance_kc = self.kcb_on(date) + self.evaporation_coefficient;
+        let weight = self.reflectance_weight(date);
+        match &self.fallback {
+            Some(fallback) if weight < 1.0 => {
+                let curve_kc =
+                    fallback.coefficient_from_date(date, wind_speed, rh_min, crop_height);
+                weight * reflectance_kc + (1.0 - weight) * curve_kc
+            }
+            _ => reflectance_kc,
+        }
+    }
+}
+
+impl KcModel for NdviKcModel {
+    fn crop_name(&self) -> &str {
+        &self.crop_name
+    }
+
+    fn planting_date(&self) -> NaiveDate {
+        self.planting_date
+    }
+
+    // End of the fallback curve, or the last observation without one
+    fn season_end(&self) -> Option<NaiveDate> {
+        match &self.fallback {
+            Some(fallback) => KcModel::season_end(fallback),
+            None => self.observations.last().map(|(date, _)| *date),
+        }
+    }
+
+    fn coefficient_from_date(
+        &self,
+        date: NaiveDate,
+        wind_speed: Option<f32>,
+        rh_min: Option<f32>,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        NdviKcModel::coefficient_from_date(self, date, wind_speed, rh_min, crop_height)
+    }
+
+    // Growth stages of the fallback curve
+    fn growth_stage_on(&self, date: NaiveDate) -> Option<GrowthStage> {
+        self.fallback
+            .as_ref()
+            .map(|fallback| fallback.growth_stage_on(date))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::corn;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap()
+    }
+
+    fn linear() -> KcbRelation {
+        KcbRelation::Linear {
+            slope: 1.25,
+            intercept: -0.1,
+        }
+    }
+
+    fn observations() -> Vec<IndexObservation> {
+        vec![
+            IndexObservation::new(date(6, 1), 0.4),
+            IndexObservation::cloudy(date(6, 9)),
+            IndexObservation::new(date(6, 17), 0.8),
+            IndexObservation::new(date(8, 20), 0.8),
+        ]
+    }
+
+    #[test]
+    fn test_vegetation_indices() {
+        assert!((ndvi(0.05, 0.45) - 0.8).abs() < 0.0001);
+        assert!((savi(0.05, 0.45, 0.5) - 0.6).abs() < 0.0001);
+        assert_eq!(ndvi(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_kcb_relations() {
+        assert!((linear().kcb(0.8) - 0.9).abs() < 0.0001);
+        assert_eq!(linear().kcb(0.0), 0.0);
+
+        let scaled = KcbRelation::Scaled {
+            vi_min: 0.15,
+            vi_max: 0.85,
+            kcb_min: 0.15,
+            kcb_max: 1.15,
+            exponent: 1.0,
+        };
+        assert!((scaled.kcb(0.5) - 0.65).abs() < 0.0001);
+        assert_eq!(scaled.kcb(0.9), 1.15);
+        assert_eq!(scaled.kcb(0.1), 0.15);
+    }
+
+    #[test]
+    fn test_index_interpolated_over_cloud_gap() {
+        let model =
+            NdviKcModel::new("corn".to_string(), date(4, 20), linear(), &observations()).unwrap();
+
+        assert_eq!(model.observations().len(), 3);
+        assert_eq!(model.index_on(date(5, 1)), 0.4);
+        assert!((model.index_on(date(6, 9)) - 0.6).abs() < 0.0001);
+        assert!((model.kcb_on(date(6, 9)) - 0.65).abs() < 0.0001);
+        assert_eq!(model.index_on(date(9, 1)), 0.8);
+        assert_eq!(KcModel::season_end(&model), Some(date(8, 20)));
+    }
+
+    #[test]
+    fn test_blend_with_fallback_curve() {
+        let model = NdviKcModel::new("corn".to_string(), date(4, 20), linear(), &observations())
+            .unwrap()
+            .with_evaporation_coefficient(0.1)
+            .with_fallback(corn(), 10);
+
+        // On an observation the reflectance Kc applies, 10 days away the curve
+        assert!((model.coefficient_from_date(date(6, 17), None, None, None) - 1.0).abs() < 0.0001);
+        assert_eq!(model.reflectance_weight(date(7, 17)), 0.0);
+        assert!((model.coefficient_from_date(date(7, 17), None, None, None) - 1.2).abs() < 0.0001);
+
+        // Halfway between the reflectance Kc of 1.0 and the curve Kc of 1.2
+        assert!((model.reflectance_weight(date(6, 22)) - 0.5).abs() < 0.0001);
+        assert!((model.coefficient_from_date(date(6, 22), None, None, None) - 1.1).abs() < 0.0001);
+        assert_eq!(KcModel::season_end(&model), Some(date(8, 18)));
+    }
+
+    #[test]
+    fn test_requires_clear_observation() {
+        let result = NdviKcModel::new(
+            "corn".to_string(),
+            date(4, 20),
+            linear(),
+            &[IndexObservation::cloudy(date(6, 1))],
+        );
+        assert!(result.is_err());
+        assert!(NdviKcModel::new("corn".to_string(), date(4, 20), linear(), &[]).is_err());
+    }
+}
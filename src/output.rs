@@ -0,0 +1,482 @@
+use crate::kc_result::KcResult;
+use crate::kcc_gs::GrowthStage;
+use crate::stage_climate::ClimateMeans;
+use chrono::NaiveDate;
+use parquet::data_type::{ByteArray, ByteArrayType, FloatType, Int32Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::format::KeyValue;
+use parquet::schema::parser::parse_message_type;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::Arc;
+
+/// Version of the column layout of `DailyRecord` in the output files, increased when a column is changed or removed.
+pub const SCHEMA_VERSION: &str = "1";
+
+// Column layout of `DailyRecord` in Parquet files, dates are days since 1970-01-01.
+const PARQUET_SCHEMA: &str = "
+message daily_record {
+    REQUIRED INT32 date (DATE);
+    REQUIRED BYTE_ARRAY crop (UTF8);
+    OPTIONAL BYTE_ARRAY growth_stage (UTF8);
+    OPTIONAL FLOAT kc;
+    OPTIONAL FLOAT tabular_kc;
+    OPTIONAL FLOAT wind_term;
+    OPTIONAL FLOAT humidity_term;
+    OPTIONAL FLOAT gdd;
+    OPTIONAL FLOAT cumulative_gdd;
+    OPTIONAL FLOAT eto;
+    OPTIONAL FLOAT etc;
+}
+";
+
+// Daily Kc, GDD and crop ET (ETc) of a crop in the column layout of the output files, ET values are in mm and values that
+// were not calculated are empty.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyRecord {
+    pub date: NaiveDate,
+    pub crop: String,
+    pub growth_stage: Option<GrowthStage>,
+    pub kc: Option<f32>,
+    pub tabular_kc: Option<f32>,
+    pub wind_term: Option<f32>,
+    pub humidity_term: Option<f32>,
+    pub gdd: Option<f32>,
+    pub cumulative_gdd: Option<f32>,
+    pub eto: Option<f32>,
+    pub etc: Option<f32>,
+}
+
+impl DailyRecord {
+    /// Creates a new `DailyRecord` of a crop on a date without values.
+    pub fn new(date: NaiveDate, crop: &str) -> Self {
+        DailyRecord {
+            date,
+            crop: crop.to_string(),
+            growth_stage: None,
+            kc: None,
+            tabular_kc: None,
+            wind_term: None,
+            humidity_term: None,
+            gdd: None,
+            cumulative_gdd: None,
+            eto: None,
+            etc: None,
+        }
+    }
+
+    /// Creates a `DailyRecord` with the growth stage, Kc and adjustment terms of a `KcResult`.
+    pub fn from_kc_result(date: NaiveDate, result: &KcResult) -> Self {
+        DailyRecord {
+            growth_stage: Some(result.growth_stage),
+            kc: Some(result.kc),
+            tabular_kc: Some(result.tabular_kc),
+            wind_term: Some(result.wind_term),
+            humidity_term: Some(result.humidity_term),
+            ..DailyRecord::new(date, &result.crop_name)
+        }
+    }
+
+    /// Sets the GDD of the day and the GDD accumulated through the day.
+    pub fn with_gdd(mut self, gdd: f32, cumulative_gdd: f32) -> Self {
+        self.gdd = Some(gdd);
+        self.cumulative_gdd = Some(cumulative_gdd);
+        self
+    }
+
+    /// Sets the reference ET (ETo) of the day in mm, and the ETc as ETo times the Kc of the record if it has one.
+    pub fn with_eto(mut self, eto: f32) -> Self {
+        self.eto = Some(eto);
+        self.etc = self.kc.map(|kc| kc * eto);
+        self
+    }
+}
+
+// Provenance of an output file, written as the first line of JSON Lines, as key-value metadata in Parquet and, for CSV, on
+// request in a JSON sidecar file (see `write_metadata_sidecar`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputMetadata {
+    pub crate_version: String,
+    pub crop_table: Option<String>,
+    pub weather_file: Option<String>,
+    pub climate: ClimateMeans,
+}
+
+impl OutputMetadata {
+    /// Creates a new `OutputMetadata` with the version of this crate and the climate defaults used where wind speed or
+    /// humidity were missing, without sources.
+    pub fn new(climate: ClimateMeans) -> Self {
+        OutputMetadata {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            crop_table: None,
+            weather_file: None,
+            climate,
+        }
+    }
+
+    /// Returns the metadata with the source of the crop table.
+    pub fn with_crop_table(mut self, crop_table: &str) -> Self {
+        self.crop_table = Some(crop_table.to_string());
+        self
+    }
+
+    /// Returns the metadata with the source of the weather file.
+    pub fn with_weather_file(mut self, weather_file: &str) -> Self {
+        self.weather_file = Some(weather_file.to_string());
+        self
+    }
+
+    /// Returns the metadata as key-value pairs in a fixed order, the sources only where they are set.
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![
+            ("crate_version", self.crate_version.clone()),
+            ("schema_version", SCHEMA_VERSION.to_string()),
+        ];
+        if let Some(crop_table) = &self.crop_table {
+            entries.push(("crop_table", crop_table.clone()));
+        }
+        if let Some(weather_file) = &self.weather_file {
+            entries.push(("weather_file", weather_file.clone()));
+        }
+        entries.push(("default_wind_speed", self.climate.wind_speed.to_string()));
+        entries.push(("default_rh_min", self.climate.rh_min.to_string()));
+        entries
+    }
+
+    // The metadata as a JSON object of strings
+    fn to_json(&self) -> serde_json::Value {
+        let entries: serde_json::Map<String, serde_json::Value> = self
+            .entries()
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), serde_json::Value::String(value)))
+            .collect();
+        serde_json::Value::Object(entries)
+    }
+}
+
+// File format of the output writers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Csv,
+    JsonLines,
+    Parquet,
+}
+
+/// Writes daily records in an output format.
+///
+/// # Parameters
+///
+/// - `writer`: The destination of the output.
+/// - `records`: A slice of `DailyRecord` to write.
+/// - `metadata`: The `OutputMetadata` of the file, not written in CSV (see `write_metadata_sidecar`).
+/// - `format`: The `OutputFormat` of the file.
+///
+/// # Returns
+///
+/// A `Result` that is an error if writing fails.
+pub fn write_records<W: Write + Send>(
+    writer: W,
+    records: &[DailyRecord],
+    metadata: &OutputMetadata,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        OutputFormat::Csv => write_csv(writer, records),
+        OutputFormat::JsonLines => write_json_lines(writer, records, metadata),
+        OutputFormat::Parquet => write_parquet(writer, records, metadata),
+    }
+}
+
+/// Writes daily records as plain CSV with a header row, without metadata so any CSV reader can read it.
+pub fn write_csv<W: Write>(
+    writer: W,
+    records: &[DailyRecord],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    if records.is_empty() {
+        csv_writer.write_record(COLUMNS)?;
+    }
+    for record in records {
+        csv_writer.serialize(record)?;
+    }
+    csv_writer.flush()?;
+    Ok(())
+}
+
+/// Writes the metadata of an output file as a JSON object `{"metadata": {...}}`, for a sidecar file next to a CSV file.
+pub fn write_metadata_sidecar<W: Write>(
+    mut writer: W,
+    metadata: &OutputMetadata,
+) -> Result<(), Box<dyn std::error::Error>> {
+    serde_json::to_writer_pretty(
+        &mut writer,
+        &serde_json::json!({ "metadata": metadata.to_json() }),
+    )?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes daily records as JSON Lines, with a first line `{"metadata": {...}}` followed by one object per record.
+pub fn write_json_lines<W: Write>(
+    mut writer: W,
+    records: &[DailyRecord],
+    metadata: &OutputMetadata,
+) -> Result<(), Box<dyn std::error::Error>> {
+    serde_json::to_writer(
+        &mut writer,
+        &serde_json::json!({ "metadata": metadata.to_json() }),
+    )?;
+    writeln!(writer)?;
+
+    for record in records {
+        serde_json::to_writer(&mut writer, record)?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes daily records as an Apache Parquet file in one row group, with the metadata as key-value metadata of the file.
+pub fn write_parquet<W: Write + Send>(
+    writer: W,
+    records: &[DailyRecord],
+    metadata: &OutputMetadata,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let schema = Arc::new(parse_message_type(PARQUET_SCHEMA)?);
+    let key_values = metadata
+        .entries()
+        .into_iter()
+        .map(|(key, value)| KeyValue::new(key.to_string(), value))
+        .collect();
+    let properties = WriterProperties::builder()
+        .set_key_value_metadata(Some(key_values))
+        .build();
+
+    let mut file_writer = SerializedFileWriter::new(writer, schema.clone(), Arc::new(properties))?;
+    let mut row_group = file_writer.next_row_group()?;
+    for field in schema.get_fields() {
+        let mut column = row_group
+            .next_column()?
+            .ok_or_else(|| format!("Column {} is missing in the row group.", field.name()))?;
+        match field.name() {
+            "date" => {
+                let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+                let days: Vec<i32> = records
+                    .iter()
+                    .map(|r| r.date.signed_duration_since(epoch).num_days() as i32)
+                    .collect();
+                column.typed::<Int32Type>().write_batch(&days, None, None)?;
+            }
+            "crop" => {
+                let crops: Vec<ByteArray> = records
+                    .iter()
+                    .map(|r| ByteArray::from(r.crop.as_str()))
+                    .collect();
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&crops, None, None)?;
+            }
+            "growth_stage" => {
+                let stages: Vec<Option<ByteArray>> = records
+                    .iter()
+                    .map(|r| r.growth_stage.map(|s| ByteArray::from(s.as_str())))
+                    .collect();
+                let (values, levels) = definition_levels(stages);
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+            name => {
+                let value = float_column(name)
+                    .ok_or_else(|| format!("Column {} has no field in DailyRecord.", name))?;
+                let values: Vec<Option<f32>> = records.iter().map(value).collect();
+                let (values, levels) = definition_levels(values);
+                column
+                    .typed::<FloatType>()
+                    .write_batch(&values, Some(&levels), None)?;
+            }
+        }
+        column.close()?;
+    }
+    row_group.close()?;
+    file_writer.close()?;
+    Ok(())
+}
+
+// Column names in the order of the fields of `DailyRecord`
+const COLUMNS: [&str; 11] = [
+    "date",
+    "crop",
+    "growth_stage",
+    "kc",
+    "tabular_kc",
+    "wind_term",
+    "humidity_term",
+    "gdd",
+    "cumulative_gdd",
+    "eto",
+    "etc",
+];
+
+// Accessor of the float column of `PARQUET_SCHEMA` with the name, if it is one
+fn float_column(name: &str) -> Option<fn(&DailyRecord) -> Option<f32>> {
+    let value: fn(&DailyRecord) -> Option<f32> = match name {
+        "kc" => |r| r.kc,
+        "tabular_kc" => |r| r.tabular_kc,
+        "wind_term" => |r| r.wind_term,
+        "humidity_term" => |r| r.humidity_term,
+        "gdd" => |r| r.gdd,
+        "cumulative_gdd" => |r| r.cumulative_gdd,
+        "eto" => |r| r.eto,
+        "etc" => |r| r.etc,
+        _ => return None,
+    };
+    Some(value)
+}
+
+// Present values and the definition level of each row of an optional column
+fn definition_levels<T>(values: Vec<Option<T>>) -> (Vec<T>, Vec<i16>) {
+    let levels = values.iter().map(|v| v.is_some() as i16).collect();
+    (values.into_iter().flatten().collect(), levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kcc_gs::CropCoefficientsGs;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::RowAccessor;
+
+    fn records() -> Vec<DailyRecord> {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        );
+        let date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let result = cc.kc_result_from_date(date, Some(3.0), Some(30.0), None);
+        vec![
+            DailyRecord::from_kc_result(date, &result).with_eto(6.0),
+            DailyRecord::new(date.succ_opt().unwrap(), "corn").with_gdd(12.5, 800.0),
+        ]
+    }
+
+    fn metadata() -> OutputMetadata {
+        OutputMetadata::new(ClimateMeans::default()).with_crop_table("fao56.toml")
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut buffer = Vec::new();
+        write_csv(&mut buffer, &records()).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert!(lines[1].starts_with("2023-07-01,corn,mid,"));
+        assert_eq!(lines[2], "2023-07-02,corn,,,,,,12.5,800.0,,");
+
+        // A plain CSV reader reads every row
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        assert_eq!(reader.headers().unwrap().len(), COLUMNS.len());
+        assert_eq!(reader.records().count(), 2);
+    }
+
+    #[test]
+    fn test_write_metadata_sidecar() {
+        let mut buffer = Vec::new();
+        write_metadata_sidecar(&mut buffer, &metadata()).unwrap();
+        let sidecar: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+
+        assert_eq!(sidecar["metadata"]["crop_table"], "fao56.toml");
+        assert_eq!(sidecar["metadata"]["schema_version"], SCHEMA_VERSION);
+        assert_eq!(
+            sidecar["metadata"]["crate_version"],
+            env!("CARGO_PKG_VERSION")
+        );
+        assert!(sidecar["metadata"]["weather_file"].is_null());
+    }
+
+    #[test]
+    fn test_metadata_sources() {
+        let metadata = OutputMetadata::new(ClimateMeans::default()).with_weather_file("mead.csv");
+        let keys: Vec<&str> = metadata.entries().iter().map(|(key, _)| *key).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "crate_version",
+                "schema_version",
+                "weather_file",
+                "default_wind_speed",
+                "default_rh_min"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_json_lines() {
+        let mut buffer = Vec::new();
+        write_json_lines(&mut buffer, &records(), &metadata()).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["metadata"]["default_rh_min"], "45");
+        assert_eq!(lines[1]["growth_stage"], "mid");
+        let kc = lines[1]["kc"].as_f64().unwrap();
+        let etc = lines[1]["etc"].as_f64().unwrap();
+        assert!((etc - kc * 6.0).abs() < 0.001);
+        assert!(lines[2]["kc"].is_null());
+    }
+
+    #[test]
+    fn test_parquet_schema_matches_columns() {
+        let schema = parse_message_type(PARQUET_SCHEMA).unwrap();
+        let names: Vec<&str> = schema.get_fields().iter().map(|f| f.name()).collect();
+        assert_eq!(names, COLUMNS);
+
+        // Every column after the growth stage is a float column of the record
+        let record = &records()[1];
+        for name in &COLUMNS[3..] {
+            assert!(float_column(name).is_some(), "{}", name);
+        }
+        assert_eq!(float_column("cumulative_gdd").unwrap()(record), Some(800.0));
+        assert!(float_column("crop").is_none());
+    }
+
+    #[test]
+    fn test_write_parquet() {
+        let path = std::env::temp_dir().join("crop_coefficients_test_write_parquet.parquet");
+        let file = std::fs::File::create(&path).unwrap();
+        write_parquet(file, &records(), &metadata()).unwrap();
+
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let file_metadata = reader.metadata().file_metadata();
+        assert_eq!(file_metadata.num_rows(), 2);
+        let key_values = file_metadata.key_value_metadata().unwrap();
+        assert!(key_values
+            .iter()
+            .any(|kv| kv.key == "schema_version" && kv.value.as_deref() == Some(SCHEMA_VERSION)));
+
+        let rows: Vec<_> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(rows[0].get_string(1).unwrap(), "corn");
+        assert_eq!(rows[0].get_string(2).unwrap(), "mid");
+        assert_eq!(rows[1].get_float(7).unwrap(), 12.5);
+        assert!(rows[1].get_float(3).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
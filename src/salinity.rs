@@ -0,0 +1,163 @@
+use crate::kcc_gs::CropCoefficientsGs;
+use chrono::NaiveDate;
+
+// Salt tolerance of a crop (FAO-56 Table 23), the threshold soil salinity ECe in dS/m where the yield starts to decline and the
+// slope of the yield decline in percent per dS/m above the threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SalinityTolerance {
+    pub threshold: f32,
+    pub slope: f32,
+}
+
+impl SalinityTolerance {
+    /// Creates a new `SalinityTolerance` with the ECe threshold in dS/m and the slope in %/(dS/m). Panics if either is
+    /// negative.
+    pub fn new(threshold: f32, slope: f32) -> Self {
+        if threshold < 0.0 || slope < 0.0 {
+            panic!("ECe threshold and slope cannot be negative.");
+        }
+
+        SalinityTolerance { threshold, slope }
+    }
+
+    /// Returns the relative yield (Ya/Ym) at a soil salinity ECe in dS/m without water stress (FAO-56 Eq. 87).
+    pub fn relative_yield(&self, ece: f32) -> f32 {
+        (1.0 - self.slope / 100.0 * (ece - self.threshold).max(0.0)).clamp(0.0, 1.0)
+    }
+}
+
+/// Calculates the salinity stress coefficient Ks without water stress (FAO-56 Eq. 89).
+///
+/// # Parameters
+///
+/// - `ece`: The mean soil salinity of the root zone ECe in dS/m.
+/// - `tolerance`: The `SalinityTolerance` of the crop.
+/// - `ky`: The seasonal yield response factor of the crop.
+///
+/// # Returns
+///
+/// The salinity stress coefficient, between 0 and 1.
+pub fn salinity_stress_coefficient(ece: f32, tolerance: &SalinityTolerance, ky: f32) -> f32 {
+    if ece <= tolerance.threshold || ky <= 0.0 {
+        return 1.0;
+    }
+    (1.0 - tolerance.slope / (ky * 100.0) * (ece - tolerance.threshold)).clamp(0.0, 1.0)
+}
+
+// Crop ET of a day reduced for soil salinity and water stress, in mm. The combined Ks is the product of the salinity and
+// water stress coefficients (FAO-56 Eq. 88).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SalinityAdjustedEtc {
+    pub date: NaiveDate,
+    pub kc: f32,
+    pub etc: f32,
+    pub salinity_ks: f32,
+    pub water_stress_ks: f32,
+    pub ks: f32,
+    pub adjusted_etc: f32,
+}
+
+/// Calculates the crop ET (ETc) of a day adjusted for soil salinity and water stress, with Kc from `coefficient_from_date`.
+///
+/// # Parameters
+///
+/// - `cc`: The `CropCoefficientsGs` of the crop with its `SalinityTolerance` and `YieldResponse`.
+/// - `date`: The date.
+/// - `eto`: The reference ET (ETo) of the day in mm.
+/// - `ece`: The mean soil salinity of the root zone ECe in dS/m.
+/// - `wind_speed`: Optional wind speed at 2 m in m/s for the Kc climate adjustment.
+/// - `rh_min`: Optional minimum relative humidity in % for the Kc climate adjustment.
+/// - `water_stress_ks`: Optional water stress coefficient of the day (see `water_stress_coefficient`), 1 when not provided.
+///
+/// # Returns
+///
+/// The `SalinityAdjustedEtc` of the day, or `None` if the crop has no salinity tolerance or yield response factor.
+pub fn salinity_adjusted_etc(
+    cc: &CropCoefficientsGs,
+    date: NaiveDate,
+    eto: f32,
+    ece: f32,
+    wind_speed: Option<f32>,
+    rh_min: Option<f32>,
+    water_stress_ks: Option<f32>,
+) -> Option<SalinityAdjustedEtc> {
+    let tolerance = cc.salinity_tolerance?;
+    let ky = cc.yield_response?.seasonal;
+
+    let kc = cc.coefficient_from_date(date, wind_speed, rh_min, None);
+    let etc = kc * eto;
+    let salinity_ks = salinity_stress_coefficient(ece, &tolerance, ky);
+    let water_stress_ks = water_stress_ks.unwrap_or(1.0).clamp(0.0, 1.0);
+    let ks = salinity_ks * water_stress_ks;
+
+    Some(SalinityAdjustedEtc {
+        date,
+        kc,
+        etc,
+        salinity_ks,
+        water_stress_ks,
+        ks,
+        adjusted_etc: ks * etc,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kcc_gs::load_crop_coefficients;
+    use crate::test_fixtures;
+    use crate::yield_response::YieldResponse;
+
+    fn corn() -> CropCoefficientsGs {
+        test_fixtures::corn()
+            .with_yield_response(YieldResponse::new(1.25, None))
+            .with_salinity_tolerance(SalinityTolerance::new(1.7, 12.0))
+    }
+
+    #[test]
+    fn test_salinity_stress_coefficient() {
+        let tolerance = SalinityTolerance::new(1.7, 12.0);
+        assert_eq!(salinity_stress_coefficient(1.0, &tolerance, 1.25), 1.0);
+
+        // 2.5 dS/m above the threshold reduces the yield by 30% and ET by 24%
+        assert!((tolerance.relative_yield(4.2) - 0.7).abs() < 0.0001);
+        assert!((salinity_stress_coefficient(4.2, &tolerance, 1.25) - 0.76).abs() < 0.0001);
+        assert_eq!(salinity_stress_coefficient(20.0, &tolerance, 1.25), 0.0);
+    }
+
+    #[test]
+    fn test_salinity_adjusted_etc() {
+        let date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let adjusted =
+            salinity_adjusted_etc(&corn(), date, 6.0, 4.2, None, None, Some(0.5)).unwrap();
+
+        assert!((adjusted.kc - 1.2).abs() < 0.0001);
+        assert!((adjusted.etc - 7.2).abs() < 0.0001);
+        assert!((adjusted.ks - 0.38).abs() < 0.0001);
+        assert!((adjusted.adjusted_etc - 2.736).abs() < 0.0001);
+
+        let unstressed = salinity_adjusted_etc(&corn(), date, 6.0, 1.0, None, None, None).unwrap();
+        assert_eq!(unstressed.adjusted_etc, unstressed.etc);
+    }
+
+    #[test]
+    fn test_salinity_adjusted_etc_requires_tolerance() {
+        let mut cc = corn();
+        cc.salinity_tolerance = None;
+        let date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        assert_eq!(
+            salinity_adjusted_etc(&cc, date, 6.0, 4.2, None, None, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_crop_table_salinity_tolerance() {
+        let crops = load_crop_coefficients().unwrap();
+        assert_eq!(
+            crops["barley"].salinity_tolerance,
+            Some(SalinityTolerance::new(8.0, 5.0))
+        );
+        assert_eq!(crops["grass"].salinity_tolerance, None);
+    }
+}
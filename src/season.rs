@@ -0,0 +1,374 @@
+use crate::kc_model::KcModel;
+use crate::kcc_gs::GrowthStage;
+use crate::weather::WeatherRecord;
+use chrono::NaiveDate;
+
+// Kc of bare soil between seasons with infrequent wetting, used after the crop is terminated.
+const BARE_SOIL_KC: f32 = 0.3;
+
+/// Calculates the Kc of the soil surface after harvest from the fraction of the surface covered with crop residue, which
+/// reduces soil evaporation by about 5% for each 10% of the surface covered (FAO-56 Chapter 11).
+///
+/// # Parameters
+///
+/// - `bare_soil_kc`: The Kc of the bare soil surface.
+/// - `residue_cover`: The fraction (0 - 1) of the soil surface covered by residue.
+///
+/// # Returns
+///
+/// The post-harvest Kc as a `f32`.
+pub fn residue_kc(bare_soil_kc: f32, residue_cover: f32) -> f32 {
+    bare_soil_kc * (1.0 - 0.5 * residue_cover.clamp(0.0, 1.0))
+}
+
+// Options of a season simulation. A killing frost ends the crop on the first day with a minimum temperature at or below
+// `killing_frost_temp` (°C) on or after `frost_after`, or without that date in the mid or late stage of the crop (on any day
+// for models without growth stages), so winter annuals and spring frosts after planting do not end the crop. The harvest
+// date ends the crop on that day, and from the day the crop ends the post-harvest Kc is used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeasonOptions {
+    pub killing_frost_temp: Option<f32>,
+    pub frost_after: Option<NaiveDate>,
+    pub harvest_date: Option<NaiveDate>,
+    pub post_harvest_kc: f32,
+}
+
+impl Default for SeasonOptions {
+    // Killing frost at -2 °C in the mid and late stages and bare soil after the season
+    fn default() -> Self {
+        SeasonOptions {
+            killing_frost_temp: Some(-2.0),
+            frost_after: None,
+            harvest_date: None,
+            post_harvest_kc: BARE_SOIL_KC,
+        }
+    }
+}
+
+// Reason the crop stopped transpiring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TerminationCause {
+    // Minimum temperature at or below the killing frost threshold
+    KillingFrost { min_temp: f32 },
+    // Harvest on the date given in the options
+    Harvest,
+    // End of the season of the Kc model
+    Maturity,
+}
+
+// First day without a transpiring crop and the cause.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Termination {
+    pub date: NaiveDate,
+    pub cause: TerminationCause,
+}
+
+// Kc of one day of a season, `crop_active` is false from the day the crop is terminated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeasonDay {
+    pub date: NaiveDate,
+    pub kc: f32,
+    pub crop_active: bool,
+}
+
+// Daily Kc of a season from planting to the end of the weather series, with the termination of the crop if it occurred.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Season {
+    pub days: Vec<SeasonDay>,
+    pub termination: Option<Termination>,
+}
+
+/// Simulates the daily Kc of a crop through a weather series, ending the crop at a killing frost, the harvest date or the end
+/// of the season of the Kc model, whichever comes first, and using the post-harvest Kc afterwards.
+///
+/// # Parameters
+///
+/// - `model`: The `KcModel` of the crop, adjusted each day with the wind speed and minimum relative humidity of the record.
+/// - `weather`: A slice of `WeatherRecord` in date order, days before planting are skipped.
+/// - `options`: The `SeasonOptions` with the killing frost threshold, harvest date and post-harvest Kc.
+///
+/// # Returns
+///
+/// A `Season` with a `SeasonDay` for each weather record from planting and the termination of the crop.
+pub fn simulate_season(
+    model: &dyn KcModel,
+    weather: &[WeatherRecord],
+    options: &SeasonOptions,
+) -> Season {
+    let mut days = Vec::new();
+    let mut termination: Option<Termination> = None;
+
+    for record in weather.iter().filter(|w| w.date >= model.planting_date()) {
+        if termination.is_none() {
+            termination = termination_on(model, record, options);
+        }
+
+        let kc = if termination.is_some() {
+            options.post_harvest_kc
+        } else {
+            model.coefficient_from_date(
+                record.date,
+                record.wind_speed_2m(),
+                record.min_relative_humidity(),
+                None,
+            )
+        };
+
+        days.push(SeasonDay {
+            date: record.date,
+            kc,
+            crop_active: termination.is_none(),
+        });
+    }
+
+    Season { days, termination }
+}
+
+// Termination of the crop on the day of the record, if any.
+fn termination_on(
+    model: &dyn KcModel,
+    record: &WeatherRecord,
+    options: &SeasonOptions,
+) -> Option<Termination> {
+    let date = record.date;
+
+    if let Some(threshold) = options.killing_frost_temp {
+        let frost_season = match options.frost_after {
+            Some(after) => date >= after,
+            // Models without growth stages are in season from planting
+            None => !matches!(
+                model.growth_stage_on(date),
+                Some(GrowthStage::Initial | GrowthStage::Development)
+            ),
+        };
+        if frost_season && record.min_temp <= threshold {
+            return Some(Termination {
+                date,
+                cause: TerminationCause::KillingFrost {
+                    min_temp: record.min_temp,
+                },
+            });
+        }
+    }
+
+    if options.harvest_date.is_some_and(|harvest| date >= harvest) {
+        return Some(Termination {
+            date,
+            cause: TerminationCause::Harvest,
+        });
+    }
+
+    if model.season_end().is_some_and(|end| date > end) {
+        return Some(Termination {
+            date,
+            cause: TerminationCause::Maturity,
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crop_table::find_variant;
+    use crate::kc_curve::KcCurve;
+    use crate::kc_gdd::CropCoefficientsGdd;
+    use crate::kc_model::GddKcModel;
+    use crate::kcc_gs::{load_crop_variants, CropCoefficientsGs};
+
+    fn corn() -> CropCoefficientsGs {
+        CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        )
+    }
+
+    // Mild weather from April to October with a single cold night
+    fn weather(frost_date: NaiveDate, frost_temp: f32) -> Vec<WeatherRecord> {
+        let start = NaiveDate::from_ymd_opt(2023, 4, 1).unwrap();
+        (0..200)
+            .map(|day| {
+                let date = start + chrono::Duration::days(day);
+                let min_temp = if date == frost_date { frost_temp } else { 10.0 };
+                WeatherRecord::new(date, 25.0, min_temp)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_killing_frost_ends_season() {
+        let frost_date = NaiveDate::from_ymd_opt(2023, 7, 15).unwrap();
+        let season = simulate_season(
+            &corn(),
+            &weather(frost_date, -3.0),
+            &SeasonOptions::default(),
+        );
+
+        assert_eq!(
+            season.days[0].date,
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap()
+        );
+        assert_eq!(
+            season.termination,
+            Some(Termination {
+                date: frost_date,
+                cause: TerminationCause::KillingFrost { min_temp: -3.0 }
+            })
+        );
+
+        let day_before = season
+            .days
+            .iter()
+            .find(|d| d.date < frost_date && d.date.succ_opt() == Some(frost_date))
+            .unwrap();
+        assert!(day_before.crop_active);
+        assert!((day_before.kc - 1.2).abs() < 0.001);
+        for day in season.days.iter().filter(|d| d.date >= frost_date) {
+            assert!(!day.crop_active);
+            assert_eq!(day.kc, 0.3);
+        }
+    }
+
+    #[test]
+    fn test_light_frost_and_early_frost_are_ignored() {
+        let frost_date = NaiveDate::from_ymd_opt(2023, 5, 1).unwrap();
+
+        // -1 °C is above the killing frost threshold
+        let season = simulate_season(
+            &corn(),
+            &weather(frost_date, -1.0),
+            &SeasonOptions::default(),
+        );
+        assert_eq!(
+            season.termination.unwrap().cause,
+            TerminationCause::Maturity
+        );
+
+        // A spring frost before the frost season does not end the crop
+        let options = SeasonOptions {
+            frost_after: NaiveDate::from_ymd_opt(2023, 7, 1),
+            ..SeasonOptions::default()
+        };
+        let season = simulate_season(&corn(), &weather(frost_date, -5.0), &options);
+        assert_eq!(
+            season.termination,
+            Some(Termination {
+                date: NaiveDate::from_ymd_opt(2023, 8, 19).unwrap(),
+                cause: TerminationCause::Maturity
+            })
+        );
+    }
+
+    #[test]
+    fn test_winter_wheat_survives_winter_freezes() {
+        let variants = load_crop_variants().unwrap();
+        let winter_wheat = find_variant(&variants["winter_wheat"], "Great Plains, Idaho").unwrap();
+        assert_eq!(
+            winter_wheat.planting_date,
+            NaiveDate::from_ymd_opt(2023, 10, 1).unwrap()
+        );
+
+        // Hard freezes from December to February in the initial stage, then mild weather until the fall
+        let start = NaiveDate::from_ymd_opt(2023, 10, 1).unwrap();
+        let weather: Vec<WeatherRecord> = (0..400)
+            .map(|day| {
+                let date = start + chrono::Duration::days(day);
+                let min_temp = if (3..=5).contains(&(day / 30)) {
+                    -12.0
+                } else {
+                    5.0
+                };
+                WeatherRecord::new(date, 15.0, min_temp)
+            })
+            .collect();
+
+        let season = simulate_season(winter_wheat, &weather, &SeasonOptions::default());
+        assert_eq!(
+            season.termination.unwrap().cause,
+            TerminationCause::Maturity
+        );
+
+        // A frost in the mid stage still ends the crop
+        let mut weather = weather;
+        let mid_date = winter_wheat.stage_timeline()[2].start_date;
+        let index = weather.iter().position(|r| r.date == mid_date).unwrap();
+        weather[index].min_temp = -5.0;
+        let season = simulate_season(winter_wheat, &weather, &SeasonOptions::default());
+        assert_eq!(
+            season.termination,
+            Some(Termination {
+                date: mid_date,
+                cause: TerminationCause::KillingFrost { min_temp: -5.0 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_killing_frost_ends_season_of_model_without_stages() {
+        let frost_date = NaiveDate::from_ymd_opt(2023, 7, 15).unwrap();
+        let curve = KcCurve::from(&corn());
+        assert_eq!(curve.growth_stage_on(frost_date), None);
+
+        let season = simulate_season(
+            &curve,
+            &weather(frost_date, -3.0),
+            &SeasonOptions::default(),
+        );
+        assert_eq!(
+            season.termination,
+            Some(Termination {
+                date: frost_date,
+                cause: TerminationCause::KillingFrost { min_temp: -3.0 }
+            })
+        );
+    }
+
+    #[test]
+    fn test_gdd_driven_season() {
+        // 7.5 GDD a day at base 10 °C, so the crop planted April 20 reaches maturity on the 107th day
+        let weather = weather(NaiveDate::from_ymd_opt(2023, 12, 1).unwrap(), 10.0);
+        let planting_date = NaiveDate::from_ymd_opt(2023, 4, 20).unwrap();
+        let cc = CropCoefficientsGdd::new(
+            "corn".to_string(),
+            (100.0, 0.3),
+            (300.0, 1.2),
+            (600.0, 1.2),
+            (800.0, 0.6),
+        );
+        let model = GddKcModel::new(cc, planting_date, 10.0, &weather);
+
+        let season = simulate_season(&model, &weather, &SeasonOptions::default());
+        assert_eq!(
+            season.termination,
+            Some(Termination {
+                date: NaiveDate::from_ymd_opt(2023, 8, 5).unwrap(),
+                cause: TerminationCause::Maturity
+            })
+        );
+        assert!((season.days[50].kc - 1.2).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_harvest_date_with_residue() {
+        let harvest_date = NaiveDate::from_ymd_opt(2023, 8, 10).unwrap();
+        let options = SeasonOptions {
+            killing_frost_temp: None,
+            harvest_date: Some(harvest_date),
+            post_harvest_kc: residue_kc(0.3, 0.6),
+            ..SeasonOptions::default()
+        };
+
+        let season = simulate_season(&corn(), &weather(harvest_date, -10.0), &options);
+
+        assert_eq!(season.termination.unwrap().date, harvest_date);
+        assert_eq!(season.termination.unwrap().cause, TerminationCause::Harvest);
+        let last = season.days[season.days.len() - 1];
+        assert!((last.kc - 0.21).abs() < 0.001);
+    }
+}
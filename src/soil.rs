@@ -0,0 +1,592 @@
+use crate::kc_ini::SoilTexture;
+use crate::kcc_gs::CropCoefficientsGs;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+// Depth of the surface soil layer that dries by evaporation in meters (FAO-56 Eq. 73), 0.10 - 0.15 m.
+const EVAPORATION_LAYER_DEPTH: f32 = 0.10;
+
+// Readily evaporable water in mm of soil layers without a texture or REW value, the middle of the FAO-56 Table 19 range of
+// the loam to clay soils.
+const DEFAULT_REW: f32 = 9.0;
+
+// Soil texture classes of FAO-56 Table 19.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoilTextureClass {
+    Sand,
+    LoamySand,
+    SandyLoam,
+    Loam,
+    SiltLoam,
+    Silt,
+    SiltClayLoam,
+    SiltyClay,
+    Clay,
+}
+
+impl SoilTextureClass {
+    pub const ALL: [SoilTextureClass; 9] = [
+        SoilTextureClass::Sand,
+        SoilTextureClass::LoamySand,
+        SoilTextureClass::SandyLoam,
+        SoilTextureClass::Loam,
+        SoilTextureClass::SiltLoam,
+        SoilTextureClass::Silt,
+        SoilTextureClass::SiltClayLoam,
+        SoilTextureClass::SiltyClay,
+        SoilTextureClass::Clay,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SoilTextureClass::Sand => "sand",
+            SoilTextureClass::LoamySand => "loamy_sand",
+            SoilTextureClass::SandyLoam => "sandy_loam",
+            SoilTextureClass::Loam => "loam",
+            SoilTextureClass::SiltLoam => "silt_loam",
+            SoilTextureClass::Silt => "silt",
+            SoilTextureClass::SiltClayLoam => "silt_clay_loam",
+            SoilTextureClass::SiltyClay => "silty_clay",
+            SoilTextureClass::Clay => "clay",
+        }
+    }
+
+    /// Returns the broad `SoilTexture` of the class, used for the Kc_ini of heavy infiltration events.
+    pub fn texture(&self) -> SoilTexture {
+        match self {
+            SoilTextureClass::Sand | SoilTextureClass::LoamySand | SoilTextureClass::SandyLoam => {
+                SoilTexture::Coarse
+            }
+            SoilTextureClass::Loam | SoilTextureClass::SiltLoam | SoilTextureClass::Silt => {
+                SoilTexture::Medium
+            }
+            SoilTextureClass::SiltClayLoam
+            | SoilTextureClass::SiltyClay
+            | SoilTextureClass::Clay => SoilTexture::Fine,
+        }
+    }
+
+    /// Returns the typical soil water characteristics of the texture, the middle of the FAO-56 Table 19 ranges.
+    pub fn properties(&self) -> SoilProperties {
+        // (field capacity, wilting point) in m³/m³, (REW, TEW) in mm for a 0.10 m evaporation layer
+        let (field_capacity, wilting_point, rew, tew) = match self {
+            SoilTextureClass::Sand => (0.12, 0.045, 4.5, 9.0),
+            SoilTextureClass::LoamySand => (0.15, 0.065, 6.0, 11.5),
+            SoilTextureClass::SandyLoam => (0.23, 0.11, 8.0, 17.5),
+            SoilTextureClass::Loam => (0.25, 0.12, 9.0, 19.0),
+            SoilTextureClass::SiltLoam => (0.29, 0.15, 9.5, 21.5),
+            SoilTextureClass::Silt => (0.32, 0.17, 9.5, 24.0),
+            SoilTextureClass::SiltClayLoam => (0.335, 0.205, 9.5, 24.5),
+            SoilTextureClass::SiltyClay => (0.36, 0.23, 10.0, 25.0),
+            SoilTextureClass::Clay => (0.36, 0.22, 10.0, 25.5),
+        };
+        SoilProperties::new(field_capacity, wilting_point, tew, rew)
+    }
+}
+
+impl fmt::Display for SoilTextureClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SoilTextureClass {
+    type Err = ParseSoilTextureClassError;
+
+    // Parses the texture names case insensitively with spaces, hyphens or underscores, also accepting "silty clay loam"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+            "sand" => Ok(SoilTextureClass::Sand),
+            "loamy_sand" => Ok(SoilTextureClass::LoamySand),
+            "sandy_loam" => Ok(SoilTextureClass::SandyLoam),
+            "loam" => Ok(SoilTextureClass::Loam),
+            "silt_loam" => Ok(SoilTextureClass::SiltLoam),
+            "silt" => Ok(SoilTextureClass::Silt),
+            "silt_clay_loam" | "silty_clay_loam" => Ok(SoilTextureClass::SiltClayLoam),
+            "silty_clay" => Ok(SoilTextureClass::SiltyClay),
+            "clay" => Ok(SoilTextureClass::Clay),
+            _ => Err(ParseSoilTextureClassError(s.to_string())),
+        }
+    }
+}
+
+// Error returned when a string is not the name of a soil texture class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseSoilTextureClassError(String);
+
+impl fmt::Display for ParseSoilTextureClassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown soil texture '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ParseSoilTextureClassError {}
+
+// Soil water characteristics of a soil layer, the volumetric water content at field capacity and wilting point in m³/m³ and
+// the total and readily evaporable water (TEW, REW) of the surface layer in mm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoilProperties {
+    pub field_capacity: f32,
+    pub wilting_point: f32,
+    pub tew: f32,
+    pub rew: f32,
+}
+
+impl SoilProperties {
+    /// Creates new `SoilProperties`. Panics if the water contents are not between 0 and 1 with the wilting point no more than
+    /// field capacity, or if REW is negative or more than TEW.
+    pub fn new(field_capacity: f32, wilting_point: f32, tew: f32, rew: f32) -> Self {
+        if !(0.0..=1.0).contains(&field_capacity)
+            || !(0.0..=field_capacity).contains(&wilting_point)
+        {
+            panic!("Water contents must be between 0 and 1 with the wilting point no more than field capacity.");
+        }
+        if rew < 0.0 || rew > tew {
+            panic!("REW must be between 0 and TEW.");
+        }
+
+        SoilProperties {
+            field_capacity,
+            wilting_point,
+            tew,
+            rew,
+        }
+    }
+
+    /// Creates new `SoilProperties` from the water contents at field capacity and wilting point in m³/m³, with TEW of a
+    /// 0.10 m evaporation layer (FAO-56 Eq. 73) and the REW in mm, no more than TEW.
+    pub fn from_water_contents(field_capacity: f32, wilting_point: f32, rew: f32) -> Self {
+        let tew = 1000.0 * (field_capacity - 0.5 * wilting_point) * EVAPORATION_LAYER_DEPTH;
+        SoilProperties::new(field_capacity, wilting_point, tew, rew.min(tew))
+    }
+
+    /// Returns the available water capacity in mm per m of soil, 1000 (θFC - θWP) (FAO-56 Eq. 82).
+    pub fn available_water_capacity(&self) -> f32 {
+        1000.0 * (self.field_capacity - self.wilting_point)
+    }
+}
+
+// Layer of a soil profile with its thickness in meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoilLayer {
+    pub thickness: f32,
+    pub properties: SoilProperties,
+}
+
+impl SoilLayer {
+    /// Creates a new `SoilLayer`. Panics if the thickness is not more than 0.
+    pub fn new(thickness: f32, properties: SoilProperties) -> Self {
+        if thickness <= 0.0 {
+            panic!("Soil layer thickness must be more than 0.");
+        }
+
+        SoilLayer {
+            thickness,
+            properties,
+        }
+    }
+}
+
+// Soil profile of layers from the surface down. The evaporation parameters TEW and REW are those of the surface layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoilProfile {
+    pub name: String,
+    pub layers: Vec<SoilLayer>,
+}
+
+// Soil water of a root zone in mm, the total available water (TAW) and the readily available water (RAW) at the depletion
+// fraction p.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootZoneWater {
+    pub root_depth: f32,
+    pub depletion_fraction: f32,
+    pub taw: f32,
+    pub raw: f32,
+}
+
+impl SoilProfile {
+    /// Creates a new `SoilProfile`. Panics if there are no layers.
+    pub fn new(name: String, layers: Vec<SoilLayer>) -> Self {
+        if layers.is_empty() {
+            panic!("Soil profile must have at least one layer.");
+        }
+
+        SoilProfile { name, layers }
+    }
+
+    /// Creates a `SoilProfile` of a single texture class to a depth in meters.
+    pub fn uniform(name: String, texture: SoilTextureClass, depth: f32) -> Self {
+        SoilProfile::new(name, vec![SoilLayer::new(depth, texture.properties())])
+    }
+
+    /// Returns the depth of the profile in meters.
+    pub fn depth(&self) -> f32 {
+        self.layers.iter().map(|layer| layer.thickness).sum()
+    }
+
+    /// Returns the total evaporable water (TEW) of the surface layer in mm.
+    pub fn tew(&self) -> f32 {
+        self.layers[0].properties.tew
+    }
+
+    /// Returns the readily evaporable water (REW) of the surface layer in mm.
+    pub fn rew(&self) -> f32 {
+        self.layers[0].properties.rew
+    }
+
+    /// Calculates the total available water (TAW) of a root zone (FAO-56 Eq. 82), summed over the layers down to the root
+    /// depth. Roots do not extend below the profile.
+    ///
+    /// # Parameters
+    ///
+    /// - `root_depth`: The depth of the root zone in meters.
+    ///
+    /// # Returns
+    ///
+    /// The total available water in mm.
+    pub fn total_available_water(&self, root_depth: f32) -> f32 {
+        let mut remaining = root_depth.max(0.0);
+        let mut taw = 0.0;
+        for layer in &self.layers {
+            let thickness = layer.thickness.min(remaining);
+            taw += layer.properties.available_water_capacity() * thickness;
+            remaining -= thickness;
+            if remaining <= 0.0 {
+                break;
+            }
+        }
+        taw
+    }
+
+    /// Calculates the readily available water (RAW) of a root zone (FAO-56 Eq. 83), RAW = p TAW.
+    ///
+    /// # Parameters
+    ///
+    /// - `root_depth`: The depth of the root zone in meters.
+    /// - `depletion_fraction`: The fraction p of TAW that can be depleted before water stress.
+    ///
+    /// # Returns
+    ///
+    /// The readily available water in mm.
+    pub fn readily_available_water(&self, root_depth: f32, depletion_fraction: f32) -> f32 {
+        depletion_fraction.clamp(0.0, 1.0) * self.total_available_water(root_depth)
+    }
+
+    /// Calculates the TAW and RAW of a crop at its maximum root depth, with the depletion fraction adjusted for the crop ET
+    /// (see `adjusted_depletion_fraction`).
+    ///
+    /// # Parameters
+    ///
+    /// - `cc`: The `CropCoefficientsGs` of the crop with its root depth and depletion fraction.
+    /// - `etc`: The crop ET in mm/day.
+    ///
+    /// # Returns
+    ///
+    /// The `RootZoneWater` of the crop, or `None` if the crop has no root depth or depletion fraction.
+    pub fn root_zone_water(&self, cc: &CropCoefficientsGs, etc: f32) -> Option<RootZoneWater> {
+        let root_depth = cc.root_depth?.max;
+        let depletion_fraction = cc.depletion_fraction_at(etc)?;
+        let taw = self.total_available_water(root_depth);
+
+        Some(RootZoneWater {
+            root_depth: root_depth.min(self.depth()),
+            depletion_fraction,
+            taw,
+            raw: depletion_fraction * taw,
+        })
+    }
+}
+
+// Layer of a soil CSV row before the layers of a profile are checked.
+struct RawLayer {
+    top: f32,
+    bottom: f32,
+    properties: SoilProperties,
+}
+
+/// Loads soil profiles from a CSV file with one row per layer, see `read_soil_profiles_csv`.
+pub fn load_soil_profiles_csv<P: AsRef<Path>>(
+    path: P,
+) -> Result<BTreeMap<String, SoilProfile>, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)?;
+    parse_soil_profiles_csv(&text)
+}
+
+/// Reads soil profiles from CSV data with one row per layer, such as horizons exported from SSURGO.
+///
+/// The columns are `profile`, the layer depths `top_depth` and `bottom_depth` in cm (or `hzdept_r` and `hzdepb_r`), and
+/// either a `texture` class or the water contents `field_capacity` and `wilting_point` in m³/m³ (or the SSURGO `wthirdbar_r`
+/// and `wfifteenbar_r` in percent by volume). Optional `tew` and `rew` columns in mm replace the values of the texture or
+/// of FAO-56 Eq. 73. Measured water contents take precedence over the texture.
+///
+/// # Parameters
+///
+/// - `reader`: The CSV data.
+///
+/// # Returns
+///
+/// A `BTreeMap<String, SoilProfile>` keyed by profile name, or an error if a column is not found, a value cannot be parsed
+/// a water content is above 1 m³/m³ or the layers of a profile do not follow each other from the surface.
+pub fn read_soil_profiles_csv<R: Read>(
+    mut reader: R,
+) -> Result<BTreeMap<String, SoilProfile>, Box<dyn std::error::Error>> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    parse_soil_profiles_csv(&text)
+}
+
+fn parse_soil_profiles_csv(
+    text: &str,
+) -> Result<BTreeMap<String, SoilProfile>, Box<dyn std::error::Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let headers = reader.headers()?.clone();
+    let column_index = |names: &[&str]| -> Option<usize> {
+        headers
+            .iter()
+            .position(|h| names.iter().any(|name| h.eq_ignore_ascii_case(name)))
+    };
+    let required = |names: &[&str]| -> Result<usize, String> {
+        column_index(names).ok_or(format!("Column {} not found in the soil file.", names[0]))
+    };
+    let profile_index = required(&["profile"])?;
+    let top_index = required(&["top_depth", "hzdept_r"])?;
+    let bottom_index = required(&["bottom_depth", "hzdepb_r"])?;
+    let texture_index = column_index(&["texture"]);
+    // Water content column in m³/m³, or in percent for the SSURGO column, with the factor to m³/m³
+    let water_content_column = |name: &str, ssurgo_name: &str| -> Option<(usize, f32)> {
+        column_index(&[name])
+            .map(|index| (index, 1.0))
+            .or(column_index(&[ssurgo_name]).map(|index| (index, 0.01)))
+    };
+    let fc_column = water_content_column("field_capacity", "wthirdbar_r");
+    let wp_column = water_content_column("wilting_point", "wfifteenbar_r");
+    let tew_index = column_index(&["tew"]);
+    let rew_index = column_index(&["rew"]);
+
+    let mut raw_layers: BTreeMap<String, Vec<RawLayer>> = BTreeMap::new();
+    for row in reader.records() {
+        let row = row?;
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| row.get(i))
+                .filter(|value| !value.is_empty())
+        };
+        let number = |index: Option<usize>| -> Result<Option<f32>, String> {
+            cell(index)
+                .map(|value| {
+                    value
+                        .parse::<f32>()
+                        .map_err(|_| format!("Invalid value {} in the soil file.", value))
+                })
+                .transpose()
+        };
+        let water_content = |column: Option<(usize, f32)>| -> Result<Option<f32>, String> {
+            let Some((index, factor)) = column else {
+                return Ok(None);
+            };
+            match number(Some(index))?.map(|value| value * factor) {
+                Some(value) if value > 1.0 => Err(format!(
+                    "Water content {} above 1 m³/m³ in the soil file.",
+                    value
+                )),
+                value => Ok(value),
+            }
+        };
+
+        let profile = cell(Some(profile_index))
+            .ok_or("Missing profile name in the soil file.")?
+            .to_string();
+        let top = number(Some(top_index))?.ok_or("Missing layer depth in the soil file.")?;
+        let bottom = number(Some(bottom_index))?.ok_or("Missing layer depth in the soil file.")?;
+        let texture = cell(texture_index)
+            .map(|value| value.parse::<SoilTextureClass>())
+            .transpose()?;
+
+        let mut properties = match (
+            water_content(fc_column)?,
+            water_content(wp_column)?,
+            texture,
+        ) {
+            (Some(fc), Some(wp), texture) => {
+                if !(0.0..=fc).contains(&wp) {
+                    return Err(format!(
+                        "Wilting point above field capacity in soil profile {}.",
+                        profile
+                    )
+                    .into());
+                }
+                let rew = texture.map_or(DEFAULT_REW, |t| t.properties().rew);
+                SoilProperties::from_water_contents(fc, wp, rew)
+            }
+            (_, _, Some(texture)) => texture.properties(),
+            _ => {
+                return Err(format!(
+                    "Soil profile {} needs a texture or the field capacity and wilting point.",
+                    profile
+                )
+                .into())
+            }
+        };
+        if let Some(tew) = number(tew_index)? {
+            properties.tew = tew;
+        }
+        if let Some(rew) = number(rew_index)? {
+            properties.rew = rew;
+        }
+        if properties.rew < 0.0 || properties.rew > properties.tew {
+            return Err(format!("REW above TEW in soil profile {}.", profile).into());
+        }
+
+        raw_layers.entry(profile).or_default().push(RawLayer {
+            top,
+            bottom,
+            properties,
+        });
+    }
+
+    let mut profiles = BTreeMap::new();
+    for (name, mut layers) in raw_layers {
+        layers.sort_by(|a, b| a.top.total_cmp(&b.top));
+        let mut depth = 0.0;
+        for layer in &layers {
+            if (layer.top - depth).abs() > 0.001 || layer.bottom <= layer.top {
+                return Err(format!(
+                    "Layers of soil profile {} must follow each other from the surface.",
+                    name
+                )
+                .into());
+            }
+            depth = layer.bottom;
+        }
+
+        let layers = layers
+            .iter()
+            .map(|layer| SoilLayer::new((layer.bottom - layer.top) / 100.0, layer.properties))
+            .collect();
+        profiles.insert(name.clone(), SoilProfile::new(name, layers));
+    }
+
+    Ok(profiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kcc_gs::load_crop_coefficients;
+
+    #[test]
+    fn test_texture_properties() {
+        assert_eq!(
+            "Silty Clay Loam".parse::<SoilTextureClass>(),
+            Ok(SoilTextureClass::SiltClayLoam)
+        );
+        assert_eq!(SoilTextureClass::SandyLoam.texture(), SoilTexture::Coarse);
+        assert!("peat".parse::<SoilTextureClass>().is_err());
+
+        let loam = SoilTextureClass::Loam.properties();
+        assert!((loam.available_water_capacity() - 130.0).abs() < 0.01);
+        for texture in SoilTextureClass::ALL {
+            let properties = texture.properties();
+            assert!(properties.rew < properties.tew, "{}", texture);
+            assert_eq!(texture.as_str().parse::<SoilTextureClass>(), Ok(texture));
+        }
+
+        // TEW of FAO-56 Eq. 73 for a 0.10 m evaporation layer
+        let properties = SoilProperties::from_water_contents(0.23, 0.10, 9.0);
+        assert!((properties.tew - 18.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_layered_total_available_water() {
+        let profile = SoilProfile::new(
+            "layered".to_string(),
+            vec![
+                SoilLayer::new(0.3, SoilTextureClass::SiltLoam.properties()),
+                SoilLayer::new(0.5, SoilTextureClass::Clay.properties()),
+            ],
+        );
+        assert!((profile.depth() - 0.8).abs() < 0.0001);
+        assert_eq!(profile.tew(), 21.5);
+
+        // 0.3 m of silt loam at 140 mm/m and 0.3 m of clay at 140 mm/m
+        assert!((profile.total_available_water(0.6) - 84.0).abs() < 0.01);
+        // Roots stop at the bottom of the profile
+        assert!((profile.total_available_water(1.5) - 112.0).abs() < 0.01);
+        assert!((profile.readily_available_water(0.6, 0.5) - 42.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_root_zone_water() {
+        let profile = SoilProfile::uniform("loam".to_string(), SoilTextureClass::Loam, 2.0);
+        let crops = load_crop_coefficients().unwrap();
+        let cc = crops["tomato"].clone();
+
+        let water = profile.root_zone_water(&cc, 5.0).unwrap();
+        assert!((water.taw - 195.0).abs() < 0.01);
+        assert!((water.raw - 0.4 * 195.0).abs() < 0.01);
+
+        let mut no_roots = cc.clone();
+        no_roots.root_depth = None;
+        assert_eq!(profile.root_zone_water(&no_roots, 5.0), None);
+    }
+
+    #[test]
+    fn test_read_soil_profiles_csv() {
+        let csv = "profile,hzdept_r,hzdepb_r,texture,wthirdbar_r,wfifteenbar_r,rew\n\
+                   field_a,20,60,,30.0,15.0,\n\
+                   field_a,0,20,silt loam,,,8\n\
+                   field_b,0,100,sandy_loam,,,\n";
+        let profiles = read_soil_profiles_csv(csv.as_bytes()).unwrap();
+        assert_eq!(profiles.len(), 2);
+
+        let field_a = &profiles["field_a"];
+        assert_eq!(field_a.layers.len(), 2);
+        assert!((field_a.depth() - 0.6).abs() < 0.0001);
+        assert_eq!(field_a.rew(), 8.0);
+        assert!((field_a.layers[1].properties.field_capacity - 0.3).abs() < 0.0001);
+        // 0.2 m at 140 mm/m and 0.4 m at 150 mm/m
+        assert!((field_a.total_available_water(1.0) - 88.0).abs() < 0.01);
+
+        assert_eq!(
+            profiles["field_b"].layers[0].properties,
+            SoilTextureClass::SandyLoam.properties()
+        );
+    }
+
+    #[test]
+    fn test_ssurgo_water_contents_are_percent() {
+        // Sand with a wilting point below 1 percent
+        let csv = "profile,hzdept_r,hzdepb_r,wthirdbar_r,wfifteenbar_r\nsand,0,100,6.5,0.8\n";
+        let profiles = read_soil_profiles_csv(csv.as_bytes()).unwrap();
+        let properties = profiles["sand"].layers[0].properties;
+        assert!((properties.field_capacity - 0.065).abs() < 0.0001);
+        assert!((properties.wilting_point - 0.008).abs() < 0.0001);
+
+        // The generic columns are fractions, percent values are an error
+        let csv = "profile,top_depth,bottom_depth,field_capacity,wilting_point\nx,0,20,0.3,0.008\n";
+        let profiles = read_soil_profiles_csv(csv.as_bytes()).unwrap();
+        assert!((profiles["x"].layers[0].properties.wilting_point - 0.008).abs() < 0.0001);
+        let csv = "profile,top_depth,bottom_depth,field_capacity,wilting_point\nx,0,20,30,15\n";
+        assert!(read_soil_profiles_csv(csv.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read_soil_profiles_csv_errors() {
+        let gap = "profile,top_depth,bottom_depth,texture\nx,0,20,loam\nx,30,60,clay\n";
+        assert!(read_soil_profiles_csv(gap.as_bytes()).is_err());
+
+        let no_water = "profile,top_depth,bottom_depth\nx,0,20\n";
+        assert!(read_soil_profiles_csv(no_water.as_bytes()).is_err());
+
+        let no_column = "profile,texture\nx,loam\n";
+        assert!(read_soil_profiles_csv(no_column.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::kcc_gs::CropCoefficientsGs;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+// Mean climate conditions used for the Kc climate adjustment, the wind speed is at 2 m height in m/s and the minimum relative
+// humidity is in percent.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClimateMeans {
+    pub wind_speed: f32,
+    pub rh_min: f32,
+}
+
+impl ClimateMeans {
+    pub fn new(wind_speed: f32, rh_min: f32) -> Self {
+        ClimateMeans { wind_speed, rh_min }
+    }
+}
+
+impl Default for ClimateMeans {
+    // FAO-56 reference climate of a sub-humid region, where no adjustment of Kc is needed
+    fn default() -> Self {
+        ClimateMeans::new(2.0, 45.0)
+    }
+}
+
+// Daily climate observation with the wind speed at 2 m height in m/s and the minimum relative humidity in percent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DailyClimate {
+    pub date: NaiveDate,
+    pub wind_speed: f32,
+    pub rh_min: f32,
+}
+
+impl DailyClimate {
+    pub fn new(date: NaiveDate, wind_speed: f32, rh_min: f32) -> Self {
+        DailyClimate {
+            date,
+            wind_speed,
+            rh_min,
+        }
+    }
+}
+
+// Mean climate over the mid-season and late season stages, used to adjust Kc_mid (FAO-56 Eq. 62) and Kc_end (FAO-56 Eq. 65).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StageClimate {
+    pub mid: ClimateMeans,
+    pub late: ClimateMeans,
+}
+
+impl StageClimate {
+    /// Averages a daily climate series over the mid-season and late season stages of a crop.
+    ///
+    /// Days of a stage that are missing from the series (e.g. days beyond the end of a forecast) are filled with the
+    /// climatological means, so a partial season can be completed with long-term averages.
+    ///
+    /// # Parameters
+    ///
+    /// - `cc`: The `CropCoefficientsGs` defining the planting date and stage lengths.
+    /// - `series`: A slice of `DailyClimate` observations, in any order.
+    /// - `climatology`: The `ClimateMeans` used for days without observations.
+    ///
+    /// # Returns
+    ///
+    /// A `StageClimate` with the mean wind speed and minimum relative humidity of the mid and late stages.
+    pub fn from_series(
+        cc: &CropCoefficientsGs,
+        series: &[DailyClimate],
+        climatology: ClimateMeans,
+    ) -> StageClimate {
+        let mid = stage_mean(
+            cc,
+            series,
+            climatology,
+            cc.development_end_kc.days,
+            cc.mid_end_kc.days,
+        );
+        let late = stage_mean(
+            cc,
+            series,
+            climatology,
+            cc.mid_end_kc.days,
+            cc.late_end_kc.days,
+        );
+
+        StageClimate { mid, late }
+    }
+}
+
+// How the climate adjustment of Kc is applied to a daily climate series.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClimateAdjustment {
+    // Adjust Kc on each day with that day's wind speed and minimum relative humidity
+    Daily,
+    // Adjust Kc_mid and Kc_end with the mean climate of the mid and late stages, filling missing days with the climatology
+    StageAveraged { climatology: ClimateMeans },
+}
+
+// Mean climate over the days after `start_day` up to and including `end_day` since planting.
+fn stage_mean(
+    cc: &CropCoefficientsGs,
+    series: &[DailyClimate],
+    climatology: ClimateMeans,
+    start_day: u16,
+    end_day: u16,
+) -> ClimateMeans {
+    if end_day <= start_day {
+        return climatology;
+    }
+
+    let mut wind_speed_sum = 0.0;
+    let mut rh_min_sum = 0.0;
+    for day in (start_day + 1)..=end_day {
+        let date = cc.planting_date + chrono::Duration::days(day as i64);
+        let climate = series
+            .iter()
+            .find(|c| c.date == date)
+            .map(|c| ClimateMeans::new(c.wind_speed, c.rh_min))
+            .unwrap_or(climatology);
+        wind_speed_sum += climate.wind_speed;
+        rh_min_sum += climate.rh_min;
+    }
+
+    let days = (end_day - start_day) as f32;
+    ClimateMeans::new(wind_speed_sum / days, rh_min_sum / days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corn() -> CropCoefficientsGs {
+        CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            3.0,
+        )
+    }
+
+    #[test]
+    fn test_stage_climate_from_series() {
+        let cc = corn();
+        let series: Vec<DailyClimate> = (51..=120)
+            .map(|day| {
+                let date = cc.planting_date + chrono::Duration::days(day);
+                if day <= 100 {
+                    DailyClimate::new(date, 3.0, 30.0)
+                } else {
+                    DailyClimate::new(date, 4.0, 20.0)
+                }
+            })
+            .collect();
+
+        let stage_climate = StageClimate::from_series(&cc, &series, ClimateMeans::default());
+
+        assert_eq!(stage_climate.mid, ClimateMeans::new(3.0, 30.0));
+        assert_eq!(stage_climate.late, ClimateMeans::new(4.0, 20.0));
+    }
+
+    #[test]
+    fn test_stage_climate_fills_missing_days_with_climatology() {
+        let cc = corn();
+        // Forecast covering only the first half of the late stage
+        let series: Vec<DailyClimate> = (101..=110)
+            .map(|day| DailyClimate::new(cc.planting_date + chrono::Duration::days(day), 4.0, 25.0))
+            .collect();
+
+        let stage_climate = StageClimate::from_series(&cc, &series, ClimateMeans::new(2.0, 35.0));
+
+        assert_eq!(stage_climate.mid, ClimateMeans::new(2.0, 35.0));
+        assert!((stage_climate.late.wind_speed - 3.0).abs() < 0.001);
+        assert!((stage_climate.late.rh_min - 30.0).abs() < 0.001);
+    }
+}
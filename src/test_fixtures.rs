@@ -0,0 +1,15 @@
+use crate::kcc_gs::CropCoefficientsGs;
+use chrono::NaiveDate;
+
+// Corn planted on April 20 with a 120 day season and a mature height of 2 m, shared by the module tests
+pub(crate) fn corn() -> CropCoefficientsGs {
+    CropCoefficientsGs::new(
+        "corn".to_string(),
+        (20, 0.3),
+        (50, 1.2),
+        (100, 1.2),
+        (120, 0.6),
+        NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+        2.0,
+    )
+}
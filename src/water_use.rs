@@ -0,0 +1,289 @@
+use crate::kc_model::KcModel;
+use crate::season::{simulate_season, Season, SeasonOptions};
+use crate::weather::WeatherRecord;
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+
+// Method to estimate the effective part of the monthly precipitation, the part stored in the root zone and used by the crop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EffectivePrecipitation {
+    // USDA Soil Conservation Service method as in CROPWAT
+    UsdaScs,
+    // Fixed fraction (0 - 1) of the precipitation
+    FixedPercentage(f32),
+    // FAO/AGLW dependable rain at 80% probability of exceedance
+    FaoDependable,
+}
+
+impl EffectivePrecipitation {
+    /// Calculates the effective precipitation of a month in mm from the total precipitation of the month in mm.
+    pub fn monthly(&self, precipitation: f32) -> f32 {
+        let p = precipitation.max(0.0);
+        let effective = match self {
+            EffectivePrecipitation::UsdaScs => {
+                if p <= 250.0 {
+                    p * (125.0 - 0.2 * p) / 125.0
+                } else {
+                    125.0 + 0.1 * p
+                }
+            }
+            EffectivePrecipitation::FixedPercentage(fraction) => p * fraction.clamp(0.0, 1.0),
+            EffectivePrecipitation::FaoDependable => {
+                if p <= 70.0 {
+                    0.6 * p - 10.0
+                } else {
+                    0.8 * p - 24.0
+                }
+            }
+        };
+        effective.max(0.0)
+    }
+
+    /// Calculates the effective precipitation in mm of part of a month, scaling the precipitation to the whole month and the
+    /// effective precipitation back to the days of the month that are included.
+    pub fn for_days(&self, precipitation: f32, days: u32, days_in_month: u32) -> f32 {
+        if days == 0 {
+            return 0.0;
+        }
+        let fraction = days as f32 / days_in_month as f32;
+        fraction * self.monthly(precipitation / fraction)
+    }
+}
+
+// Water use of a crop in a calendar month of the season, in mm. The effective precipitation is no more than the ETc and the
+// net irrigation requirement is the ETc not met by effective precipitation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonthlyWaterUse {
+    pub year: i32,
+    pub month: u32,
+    pub days: u32,
+    pub eto: f32,
+    pub etc: f32,
+    pub precipitation: f32,
+    pub effective_precipitation: f32,
+    pub net_irrigation_requirement: f32,
+}
+
+// Water use of a crop over its season from planting to the end of the crop, in mm, with the monthly breakdown. The
+// consumptive use of the crop is its ETc.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonalWaterUse {
+    pub crop_name: String,
+    pub planting_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    pub months: Vec<MonthlyWaterUse>,
+    pub missing_eto_days: u32,
+}
+
+impl SeasonalWaterUse {
+    /// Returns the reference ET (ETo) of the season in mm.
+    pub fn eto(&self) -> f32 {
+        self.months.iter().map(|m| m.eto).sum()
+    }
+
+    /// Returns the crop ET (ETc) of the season in mm.
+    pub fn etc(&self) -> f32 {
+        self.months.iter().map(|m| m.etc).sum()
+    }
+
+    /// Returns the consumptive use of the crop over the season in mm, its ETc.
+    pub fn consumptive_use(&self) -> f32 {
+        self.etc()
+    }
+
+    /// Returns the precipitation of the season in mm.
+    pub fn precipitation(&self) -> f32 {
+        self.months.iter().map(|m| m.precipitation).sum()
+    }
+
+    /// Returns the effective precipitation of the season in mm.
+    pub fn effective_precipitation(&self) -> f32 {
+        self.months.iter().map(|m| m.effective_precipitation).sum()
+    }
+
+    /// Returns the net irrigation requirement of the season in mm.
+    pub fn net_irrigation_requirement(&self) -> f32 {
+        self.months
+            .iter()
+            .map(|m| m.net_irrigation_requirement)
+            .sum()
+    }
+}
+
+/// Summarizes the water use of a crop over a season simulation, from planting to the killing frost, harvest or end of the
+/// season of the Kc model.
+///
+/// # Parameters
+///
+/// - `model`: The `KcModel` of the crop.
+/// - `weather`: A slice of `WeatherRecord` in date order with the ETo and precipitation in mm, days without ETo are counted as
+///   missing and days without precipitation as dry.
+/// - `options`: The `SeasonOptions` of the season simulation.
+/// - `method`: The `EffectivePrecipitation` method.
+///
+/// # Returns
+///
+/// The `SeasonalWaterUse` of the crop.
+pub fn seasonal_water_use(
+    model: &dyn KcModel,
+    weather: &[WeatherRecord],
+    options: &SeasonOptions,
+    method: EffectivePrecipitation,
+) -> SeasonalWaterUse {
+    let season = simulate_season(model, weather, options);
+    season_water_use(model, &season, weather, method)
+}
+
+// Water use of a crop over a season simulation of the Kc model on the weather, see `seasonal_water_use`.
+pub(crate) fn season_water_use(
+    model: &dyn KcModel,
+    season: &Season,
+    weather: &[WeatherRecord],
+    method: EffectivePrecipitation,
+) -> SeasonalWaterUse {
+    let weather_by_date: BTreeMap<NaiveDate, &WeatherRecord> =
+        weather.iter().map(|r| (r.date, r)).collect();
+
+    let mut missing_eto_days = 0;
+    let mut totals: BTreeMap<(i32, u32), MonthlyWaterUse> = BTreeMap::new();
+    for day in season.days.iter().filter(|d| d.crop_active) {
+        let record = weather_by_date[&day.date];
+        let month = totals
+            .entry((day.date.year(), day.date.month()))
+            .or_insert(MonthlyWaterUse {
+                year: day.date.year(),
+                month: day.date.month(),
+                days: 0,
+                eto: 0.0,
+                etc: 0.0,
+                precipitation: 0.0,
+                effective_precipitation: 0.0,
+                net_irrigation_requirement: 0.0,
+            });
+
+        month.days += 1;
+        month.precipitation += record.precipitation.unwrap_or(0.0);
+        match record.eto {
+            Some(eto) => {
+                month.eto += eto;
+                month.etc += day.kc * eto;
+            }
+            None => missing_eto_days += 1,
+        }
+    }
+
+    let months = totals
+        .into_values()
+        .map(|mut month| {
+            let days_in_month = days_in_month(month.year, month.month);
+            month.effective_precipitation = method
+                .for_days(month.precipitation, month.days, days_in_month)
+                .min(month.etc);
+            month.net_irrigation_requirement = month.etc - month.effective_precipitation;
+            month
+        })
+        .collect();
+
+    SeasonalWaterUse {
+        crop_name: model.crop_name().to_string(),
+        planting_date: model.planting_date(),
+        end_date: season.termination.map(|t| t.date),
+        months,
+        missing_eto_days,
+    }
+}
+
+// Number of days of a calendar month.
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap()
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1).unwrap()
+    };
+    next.signed_duration_since(first).num_days() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::corn;
+
+    // 5 mm/day of ETo from April through October with 2 mm of rain every day
+    fn weather() -> Vec<WeatherRecord> {
+        let start = NaiveDate::from_ymd_opt(2023, 4, 1).unwrap();
+        (0..214)
+            .map(|day| {
+                WeatherRecord::new(start + chrono::Duration::days(day), 28.0, 15.0)
+                    .with_eto(5.0)
+                    .with_precipitation(2.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_effective_precipitation_methods() {
+        // CROPWAT examples
+        assert!((EffectivePrecipitation::UsdaScs.monthly(100.0) - 84.0).abs() < 0.001);
+        assert!((EffectivePrecipitation::UsdaScs.monthly(300.0) - 155.0).abs() < 0.001);
+        assert!((EffectivePrecipitation::FaoDependable.monthly(50.0) - 20.0).abs() < 0.001);
+        assert!((EffectivePrecipitation::FaoDependable.monthly(100.0) - 56.0).abs() < 0.001);
+        assert_eq!(EffectivePrecipitation::FaoDependable.monthly(10.0), 0.0);
+        assert_eq!(
+            EffectivePrecipitation::FixedPercentage(0.8).monthly(50.0),
+            40.0
+        );
+
+        // Half of a month with half of the rain has half the effective precipitation
+        let half = EffectivePrecipitation::FaoDependable.for_days(50.0, 15, 30);
+        assert!((half - 28.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_seasonal_water_use() {
+        let summary = seasonal_water_use(
+            &corn(),
+            &weather(),
+            &SeasonOptions::default(),
+            EffectivePrecipitation::FixedPercentage(0.5),
+        );
+
+        // The season runs from April 20 through August 18, the end of the Kc curve
+        assert_eq!(summary.crop_name, "corn");
+        assert_eq!(summary.end_date, NaiveDate::from_ymd_opt(2023, 8, 19));
+        assert_eq!(summary.months.len(), 5);
+        assert_eq!(summary.months[0].days, 11);
+        assert_eq!(summary.months.iter().map(|m| m.days).sum::<u32>(), 121);
+        assert_eq!(summary.missing_eto_days, 0);
+
+        assert!((summary.eto() - 605.0).abs() < 0.01);
+        assert!((summary.precipitation() - 242.0).abs() < 0.01);
+        assert!((summary.effective_precipitation() - 121.0).abs() < 0.01);
+        assert!((summary.net_irrigation_requirement() - (summary.etc() - 121.0)).abs() < 0.01);
+        assert_eq!(summary.consumptive_use(), summary.etc());
+
+        // July is mid-season with a Kc of 1.2 through the 29th, then late season
+        let july = summary.months[3];
+        assert_eq!(july.month, 7);
+        assert!(july.etc > 29.0 * 6.0 && july.etc < 31.0 * 6.0);
+    }
+
+    #[test]
+    fn test_effective_precipitation_limited_to_etc() {
+        let weather: Vec<WeatherRecord> = weather()
+            .into_iter()
+            .map(|r| r.with_precipitation(20.0))
+            .collect();
+        let summary = seasonal_water_use(
+            &corn(),
+            &weather,
+            &SeasonOptions::default(),
+            EffectivePrecipitation::UsdaScs,
+        );
+
+        for month in &summary.months {
+            assert!(month.effective_precipitation <= month.etc);
+            assert!(month.net_irrigation_requirement >= 0.0);
+        }
+    }
+}
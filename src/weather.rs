@@ -0,0 +1,285 @@
+use crate::kcc_gs::CropCoefficientsGs;
+use crate::stage_climate::DailyClimate;
+use chrono::{Datelike, NaiveDate};
+
+/// Converts a wind speed measured at an arbitrary height above the ground to the wind speed at 2 m using the logarithmic
+/// wind profile (FAO-56 Eq. 47).
+///
+/// # Parameters
+///
+/// - `wind_speed`: The measured wind speed in m/s.
+/// - `height`: The height of the measurement above the ground surface in meters.
+///
+/// # Returns
+///
+/// The wind speed at 2 m above the ground surface in m/s. Panics if the height is not above 0.1 m, where the profile is undefined.
+pub fn wind_speed_at_2m(wind_speed: f32, height: f32) -> f32 {
+    if height <= 0.1 {
+        panic!("Wind measurement height must be above 0.1 m.");
+    }
+
+    wind_speed * 4.87 / (67.8 * height - 5.42).ln()
+}
+
+/// Calculates the saturation vapor pressure at the given air temperature (FAO-56 Eq. 11).
+///
+/// # Parameters
+///
+/// - `temp`: The air temperature in degrees Celsius.
+///
+/// # Returns
+///
+/// The saturation vapor pressure in kPa.
+pub fn saturation_vapor_pressure(temp: f32) -> f32 {
+    0.6108 * (17.27 * temp / (temp + 237.3)).exp()
+}
+
+/// Calculates the actual vapor pressure from the dew point temperature (FAO-56 Eq. 14).
+///
+/// # Parameters
+///
+/// - `dew_point`: The dew point temperature in degrees Celsius.
+///
+/// # Returns
+///
+/// The actual vapor pressure in kPa.
+pub fn actual_vapor_pressure(dew_point: f32) -> f32 {
+    saturation_vapor_pressure(dew_point)
+}
+
+/// Derives the minimum relative humidity of the day from the maximum air temperature and the dew point, assuming the
+/// vapor pressure is constant over the day so the relative humidity is lowest at the maximum temperature.
+///
+/// # Parameters
+///
+/// - `max_temp`: The maximum air temperature of the day in degrees Celsius.
+/// - `dew_point`: The dew point temperature in degrees Celsius.
+///
+/// # Returns
+///
+/// The minimum relative humidity in percent, no more than 100%.
+pub fn rh_min_from_dew_point(max_temp: f32, dew_point: f32) -> f32 {
+    (100.0 * actual_vapor_pressure(dew_point) / saturation_vapor_pressure(max_temp)).min(100.0)
+}
+
+/// Calculates the daylight hours at a latitude on a date from the sunset hour angle (FAO-56 Eq. 24, 25 and 34).
+///
+/// # Parameters
+///
+/// - `latitude`: The latitude in decimal degrees, positive in the northern hemisphere.
+/// - `date`: The `NaiveDate` of the day.
+///
+/// # Returns
+///
+/// The maximum possible duration of sunshine (day length) in hours.
+pub fn day_length(latitude: f32, date: NaiveDate) -> f32 {
+    let day_of_year = date.ordinal() as f32;
+    let latitude = latitude.to_radians();
+    let declination = 0.409 * (2.0 * std::f32::consts::PI / 365.0 * day_of_year - 1.39).sin();
+    let sunset_hour_angle = (-latitude.tan() * declination.tan())
+        .clamp(-1.0, 1.0)
+        .acos();
+    24.0 / std::f32::consts::PI * sunset_hour_angle
+}
+
+// Daily weather record from a station, temperatures are in degrees Celsius, the wind speed is in m/s measured at
+// `wind_height` meters, the minimum relative humidity is in percent and the precipitation and reference ET (ETo) are in mm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherRecord {
+    pub date: NaiveDate,
+    pub max_temp: f32,
+    pub min_temp: f32,
+    pub wind_speed: Option<f32>,
+    pub wind_height: f32,
+    pub rh_min: Option<f32>,
+    pub dew_point: Option<f32>,
+    pub precipitation: Option<f32>,
+    pub eto: Option<f32>,
+}
+
+impl WeatherRecord {
+    /// Creates a new `WeatherRecord` with the daily temperature extremes and no wind or humidity data.
+    pub fn new(date: NaiveDate, max_temp: f32, min_temp: f32) -> Self {
+        WeatherRecord {
+            date,
+            max_temp,
+            min_temp,
+            wind_speed: None,
+            wind_height: 2.0,
+            rh_min: None,
+            dew_point: None,
+            precipitation: None,
+            eto: None,
+        }
+    }
+
+    /// Sets the wind speed in m/s and the height of the anemometer above the ground in meters.
+    pub fn with_wind_speed(mut self, wind_speed: f32, height: f32) -> Self {
+        self.wind_speed = Some(wind_speed);
+        self.wind_height = height;
+        self
+    }
+
+    /// Sets the measured minimum relative humidity in percent.
+    pub fn with_rh_min(mut self, rh_min: f32) -> Self {
+        self.rh_min = Some(rh_min);
+        self
+    }
+
+    /// Sets the dew point temperature in degrees Celsius.
+    pub fn with_dew_point(mut self, dew_point: f32) -> Self {
+        self.dew_point = Some(dew_point);
+        self
+    }
+
+    /// Sets the precipitation of the day in mm.
+    pub fn with_precipitation(mut self, precipitation: f32) -> Self {
+        self.precipitation = Some(precipitation);
+        self
+    }
+
+    /// Sets the grass reference evapotranspiration (ETo) of the day in mm.
+    pub fn with_eto(mut self, eto: f32) -> Self {
+        self.eto = Some(eto);
+        self
+    }
+
+    /// Returns the wind speed converted to 2 m height, if the record has a wind speed.
+    pub fn wind_speed_2m(&self) -> Option<f32> {
+        self.wind_speed
+            .map(|wind_speed| wind_speed_at_2m(wind_speed, self.wind_height))
+    }
+
+    /// Returns the minimum relative humidity in percent, using the measured value if present and otherwise deriving it
+    /// from the maximum temperature and the dew point.
+    pub fn min_relative_humidity(&self) -> Option<f32> {
+        self.rh_min.or_else(|| {
+            self.dew_point
+                .map(|dew_point| rh_min_from_dew_point(self.max_temp, dew_point))
+        })
+    }
+
+    /// Returns the `DailyClimate` of the record for the Kc climate adjustment, if both the wind speed and the minimum
+    /// relative humidity are available.
+    pub fn daily_climate(&self) -> Option<DailyClimate> {
+        match (self.wind_speed_2m(), self.min_relative_humidity()) {
+            (Some(wind_speed), Some(rh_min)) => {
+                Some(DailyClimate::new(self.date, wind_speed, rh_min))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl CropCoefficientsGs {
+    /// Calculates the crop coefficient (Kc) on the date of a station weather record, converting the wind speed to 2 m and
+    /// deriving the minimum relative humidity from the dew point as needed (see `coefficient_from_date`).
+    ///
+    /// # Parameters
+    ///
+    /// - `weather`: The `WeatherRecord` of the day, missing wind or humidity fall back to the defaults of `coefficient_from_date`.
+    /// - `crop_height`: Optional measured crop height in meters (default: the crop height profile).
+    ///
+    /// # Returns
+    ///
+    /// The calculated Kc value as f32.
+    pub fn coefficient_from_weather(
+        &self,
+        weather: &WeatherRecord,
+        crop_height: Option<f32>,
+    ) -> f32 {
+        self.coefficient_from_date(
+            weather.date,
+            weather.wind_speed_2m(),
+            weather.min_relative_humidity(),
+            crop_height,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wind_speed_at_2m() {
+        // FAO-56 Example 14: 3.2 m/s measured at 10 m is 2.4 m/s at 2 m
+        assert!((wind_speed_at_2m(3.2, 10.0) - 2.393).abs() < 0.005);
+        assert!((wind_speed_at_2m(3.0, 2.0) - 3.0).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_vapor_pressure() {
+        // FAO-56 Table 2.3 and Example 5
+        assert!((saturation_vapor_pressure(24.5) - 3.075).abs() < 0.005);
+        assert!((saturation_vapor_pressure(15.0) - 1.705).abs() < 0.005);
+        assert!((actual_vapor_pressure(17.0) - 1.938).abs() < 0.005);
+    }
+
+    #[test]
+    fn test_day_length() {
+        // FAO-56 Example 9: 3 September at 20° S has 11.7 hours of daylight
+        let date = NaiveDate::from_ymd_opt(2023, 9, 3).unwrap();
+        assert!((day_length(-20.0, date) - 11.7).abs() < 0.1);
+
+        // Longest day in Nebraska and polar night
+        let solstice = NaiveDate::from_ymd_opt(2023, 6, 21).unwrap();
+        assert!((day_length(41.0, solstice) - 14.95).abs() < 0.05);
+        assert_eq!(day_length(-80.0, solstice), 0.0);
+    }
+
+    #[test]
+    fn test_rh_min_from_dew_point() {
+        let rh_min = rh_min_from_dew_point(30.0, 15.0);
+        assert!(
+            (rh_min - 40.2).abs() < 0.1,
+            "Expected RHmin of 40.2%, got {}",
+            rh_min
+        );
+
+        // The dew point cannot exceed the air temperature
+        assert_eq!(rh_min_from_dew_point(10.0, 12.0), 100.0);
+    }
+
+    #[test]
+    fn test_weather_record_climate_inputs() {
+        let date = NaiveDate::from_ymd_opt(2023, 7, 1).unwrap();
+        let record = WeatherRecord::new(date, 30.0, 18.0)
+            .with_wind_speed(3.2, 10.0)
+            .with_dew_point(15.0);
+
+        let climate = record.daily_climate().expect("Expected wind and humidity");
+        assert!((climate.wind_speed - 2.393).abs() < 0.005);
+        assert!((climate.rh_min - 40.2).abs() < 0.1);
+
+        // A measured RHmin takes precedence over the dew point
+        assert_eq!(record.with_rh_min(35.0).min_relative_humidity(), Some(35.0));
+        assert_eq!(WeatherRecord::new(date, 30.0, 18.0).daily_climate(), None);
+    }
+
+    #[test]
+    fn test_coefficient_from_weather() {
+        let cc = CropCoefficientsGs::new(
+            "corn".to_string(),
+            (20, 0.3),
+            (50, 1.2),
+            (100, 1.2),
+            (120, 0.6),
+            NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+            2.0,
+        );
+        let record = WeatherRecord::new(NaiveDate::from_ymd_opt(2023, 7, 1).unwrap(), 30.0, 18.0)
+            .with_wind_speed(3.2, 10.0)
+            .with_dew_point(15.0);
+
+        let kc = cc.coefficient_from_weather(&record, None);
+        let expected = cc.coefficient_from_date(
+            record.date,
+            record.wind_speed_2m(),
+            record.min_relative_humidity(),
+            None,
+        );
+        assert_eq!(kc, expected);
+        assert!(kc > 1.2);
+    }
+}
@@ -0,0 +1,796 @@
+use crate::weather::WeatherRecord;
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+// Fields of a daily record in the order of `RawDay::values`, the names are used in the QC flags.
+const FIELDS: [&str; 7] = [
+    "max_temp",
+    "min_temp",
+    "wind_speed",
+    "rh_min",
+    "dew_point",
+    "precipitation",
+    "eto",
+];
+const PRECIPITATION: usize = 5;
+
+// Unit of the temperature columns of a weather file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    fn to_celsius(self, value: f32) -> f32 {
+        match self {
+            TemperatureUnit::Celsius => value,
+            TemperatureUnit::Fahrenheit => (value - 32.0) / 1.8,
+        }
+    }
+}
+
+// Unit of the wind speed column of a weather file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedUnit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+}
+
+impl SpeedUnit {
+    fn to_meters_per_second(self, value: f32) -> f32 {
+        match self {
+            SpeedUnit::MetersPerSecond => value,
+            SpeedUnit::KilometersPerHour => value / 3.6,
+            SpeedUnit::MilesPerHour => value * 0.44704,
+        }
+    }
+}
+
+// Unit of the precipitation and ETo columns of a weather file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthUnit {
+    Millimeters,
+    Inches,
+}
+
+impl DepthUnit {
+    fn to_millimeters(self, value: f32) -> f32 {
+        match self {
+            DepthUnit::Millimeters => value,
+            DepthUnit::Inches => value * 25.4,
+        }
+    }
+}
+
+// Column mapping and units of a daily weather CSV. The file has a header row after `skip_lines` CSV rows of station
+// information (blank lines are not counted), optional columns are not read when `None` and the cells in `missing_values` are missing data. A trace of
+// precipitation ("T") is read as 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherCsvLayout {
+    pub date_column: String,
+    pub date_format: String,
+    pub max_temp_column: String,
+    pub min_temp_column: String,
+    pub wind_speed_column: Option<String>,
+    pub rh_min_column: Option<String>,
+    pub dew_point_column: Option<String>,
+    pub precipitation_column: Option<String>,
+    pub eto_column: Option<String>,
+    pub temperature_unit: TemperatureUnit,
+    pub wind_unit: SpeedUnit,
+    pub depth_unit: DepthUnit,
+    pub wind_height: f32,
+    pub delimiter: u8,
+    pub skip_lines: usize,
+    pub missing_values: Vec<String>,
+}
+
+impl WeatherCsvLayout {
+    /// Creates a new `WeatherCsvLayout` with the date and temperature columns, ISO dates, metric units, wind measured at
+    /// 2 m, comma delimiters and empty, "NA" or "M" cells as missing data.
+    pub fn new(date_column: &str, max_temp_column: &str, min_temp_column: &str) -> Self {
+        WeatherCsvLayout {
+            date_column: date_column.to_string(),
+            date_format: "%Y-%m-%d".to_string(),
+            max_temp_column: max_temp_column.to_string(),
+            min_temp_column: min_temp_column.to_string(),
+            wind_speed_column: None,
+            rh_min_column: None,
+            dew_point_column: None,
+            precipitation_column: None,
+            eto_column: None,
+            temperature_unit: TemperatureUnit::Celsius,
+            wind_unit: SpeedUnit::MetersPerSecond,
+            depth_unit: DepthUnit::Millimeters,
+            wind_height: 2.0,
+            delimiter: b',',
+            skip_lines: 0,
+            missing_values: vec!["".to_string(), "NA".to_string(), "M".to_string()],
+        }
+    }
+
+    /// Creates the layout of an HPRCC (ACIS) daily station export, with °F temperatures, precipitation in inches and
+    /// "M" for missing data.
+    pub fn hprcc() -> Self {
+        WeatherCsvLayout {
+            precipitation_column: Some("Precipitation".to_string()),
+            temperature_unit: TemperatureUnit::Fahrenheit,
+            depth_unit: DepthUnit::Inches,
+            ..WeatherCsvLayout::new("Date", "MaxTemperature", "MinTemperature")
+        }
+    }
+
+    /// Creates the layout of a Nebraska AWDN daily station export from HPRCC, with US units, month/day/year dates, the
+    /// anemometer at 3 m and -999 for missing data.
+    pub fn awdn() -> Self {
+        let mut layout = WeatherCsvLayout {
+            date_format: "%m/%d/%Y".to_string(),
+            wind_speed_column: Some("Avg Wind Speed (mph)".to_string()),
+            rh_min_column: Some("Min RH (%)".to_string()),
+            precipitation_column: Some("Precip (in)".to_string()),
+            eto_column: Some("ETo (in)".to_string()),
+            temperature_unit: TemperatureUnit::Fahrenheit,
+            wind_unit: SpeedUnit::MilesPerHour,
+            depth_unit: DepthUnit::Inches,
+            wind_height: 3.0,
+            ..WeatherCsvLayout::new("Date", "Max Temp (F)", "Min Temp (F)")
+        };
+        layout.missing_values.push("-999".to_string());
+        layout
+    }
+
+    /// Sets the format of the date column, as in `NaiveDate::parse_from_str`.
+    pub fn with_date_format(mut self, date_format: &str) -> Self {
+        self.date_format = date_format.to_string();
+        self
+    }
+
+    /// Sets the wind speed column, its unit and the height of the anemometer in meters.
+    pub fn with_wind_speed(mut self, column: &str, unit: SpeedUnit, height: f32) -> Self {
+        self.wind_speed_column = Some(column.to_string());
+        self.wind_unit = unit;
+        self.wind_height = height;
+        self
+    }
+
+    /// Sets the minimum relative humidity column in percent.
+    pub fn with_rh_min(mut self, column: &str) -> Self {
+        self.rh_min_column = Some(column.to_string());
+        self
+    }
+
+    /// Sets the dew point column, in the temperature unit of the layout.
+    pub fn with_dew_point(mut self, column: &str) -> Self {
+        self.dew_point_column = Some(column.to_string());
+        self
+    }
+
+    /// Sets the precipitation column, in the depth unit of the layout.
+    pub fn with_precipitation(mut self, column: &str) -> Self {
+        self.precipitation_column = Some(column.to_string());
+        self
+    }
+
+    /// Sets the reference ET (ETo) column, in the depth unit of the layout.
+    pub fn with_eto(mut self, column: &str) -> Self {
+        self.eto_column = Some(column.to_string());
+        self
+    }
+
+    // Column name of each field, in the order of `FIELDS`
+    fn columns(&self) -> [Option<&str>; 7] {
+        [
+            Some(self.max_temp_column.as_str()),
+            Some(self.min_temp_column.as_str()),
+            self.wind_speed_column.as_deref(),
+            self.rh_min_column.as_deref(),
+            self.dew_point_column.as_deref(),
+            self.precipitation_column.as_deref(),
+            self.eto_column.as_deref(),
+        ]
+    }
+
+    // Converts a value of a field to the units of `WeatherRecord`
+    fn convert(&self, field: usize, value: f32) -> f32 {
+        match field {
+            0 | 1 | 4 => self.temperature_unit.to_celsius(value),
+            2 => self.wind_unit.to_meters_per_second(value),
+            5 | 6 => self.depth_unit.to_millimeters(value),
+            _ => value,
+        }
+    }
+}
+
+// Method to fill missing days and values of the temperature, wind, humidity and ETo fields. Linear interpolation fills
+// gaps of up to `max_gap_days` between two measured days, climatology fills a day with the mean of the same calendar day
+// in the other years of the record. Precipitation is never filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapFill {
+    None,
+    Linear { max_gap_days: usize },
+    Climatology,
+}
+
+// Quality control finding of a day in a weather file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QcFlag {
+    // Minimum temperature above the maximum temperature, both are removed
+    MinAboveMax {
+        max_temp: f32,
+        min_temp: f32,
+    },
+    // Value outside the plausible range of the field, it is removed
+    OutOfRange {
+        field: &'static str,
+        value: f32,
+    },
+    // Cell that is not a number, it is treated as missing
+    InvalidValue {
+        field: &'static str,
+    },
+    // Row with a date that cannot be parsed on line `line` of the file, it is ignored
+    InvalidDate {
+        line: u64,
+    },
+    // Second row of a date, it is ignored
+    DuplicateDate,
+    // Date without a row between the first and last date of the file
+    MissingDate,
+    // Missing value after gap filling, days without temperatures are left out of the series
+    MissingValue {
+        field: &'static str,
+    },
+    // Value filled by the gap fill method
+    Filled {
+        field: &'static str,
+        method: GapFill,
+    },
+}
+
+impl fmt::Display for QcFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QcFlag::MinAboveMax { max_temp, min_temp } => write!(
+                f,
+                "Minimum temperature {:.1} °C is above the maximum temperature {:.1} °C.",
+                min_temp, max_temp
+            ),
+            QcFlag::OutOfRange { field, value } => {
+                write!(f, "Value {} of {} is out of range.", value, field)
+            }
+            QcFlag::InvalidValue { field } => write!(f, "Value of {} is not a number.", field),
+            QcFlag::InvalidDate { line } => write!(f, "Date on line {} is not a valid date.", line),
+            QcFlag::DuplicateDate => write!(f, "Date appears more than once."),
+            QcFlag::MissingDate => write!(f, "Date is missing."),
+            QcFlag::MissingValue { field } => write!(f, "Value of {} is missing.", field),
+            QcFlag::Filled { field, method } => {
+                write!(f, "Value of {} filled by {:?}.", field, method)
+            }
+        }
+    }
+}
+
+// Quality control finding on a date, or on a row without a valid date.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QcIssue {
+    pub date: Option<NaiveDate>,
+    pub flag: QcFlag,
+}
+
+// Validated daily weather series with the quality control findings in date order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherSeries {
+    pub records: Vec<WeatherRecord>,
+    pub issues: Vec<QcIssue>,
+}
+
+impl WeatherSeries {
+    /// Returns the quality control flags of a date.
+    pub fn flags_on(&self, date: NaiveDate) -> Vec<QcFlag> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.date == Some(date))
+            .map(|issue| issue.flag)
+            .collect()
+    }
+}
+
+// Values of a day as read, in the order of `FIELDS` and converted to the units of `WeatherRecord`.
+#[derive(Debug, Clone, Copy, Default)]
+struct RawDay {
+    values: [Option<f32>; 7],
+}
+
+/// Loads a daily weather CSV file into a validated `WeatherSeries`.
+///
+/// # Parameters
+///
+/// - `path`: The path of the CSV file.
+/// - `layout`: The `WeatherCsvLayout` with the column mapping and units of the file.
+/// - `gap_fill`: The `GapFill` method for missing days and values.
+///
+/// # Returns
+///
+/// A `Result` with the `WeatherSeries`, or an error if the file cannot be read or a column is not found. Rows with a date
+/// that cannot be parsed are left out and flagged.
+pub fn load_weather_csv<P: AsRef<Path>>(
+    path: P,
+    layout: &WeatherCsvLayout,
+    gap_fill: GapFill,
+) -> Result<WeatherSeries, Box<dyn std::error::Error>> {
+    let text = fs::read_to_string(path)?;
+    parse_weather_csv(&text, layout, gap_fill)
+}
+
+/// Reads daily weather CSV data into a validated `WeatherSeries` (see `load_weather_csv`).
+pub fn read_weather_csv<R: Read>(
+    mut reader: R,
+    layout: &WeatherCsvLayout,
+    gap_fill: GapFill,
+) -> Result<WeatherSeries, Box<dyn std::error::Error>> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    parse_weather_csv(&text, layout, gap_fill)
+}
+
+fn parse_weather_csv(
+    text: &str,
+    layout: &WeatherCsvLayout,
+    gap_fill: GapFill,
+) -> Result<WeatherSeries, Box<dyn std::error::Error>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(layout.delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    // The station information before the header is skipped as records, so quoted cells may span lines
+    let mut rows = reader.records();
+    let headers = rows
+        .nth(layout.skip_lines)
+        .ok_or("The weather file has no header row.")??;
+    let column_index = |name: &str| -> Result<usize, String> {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or(format!("Column {} not found in the weather file.", name))
+    };
+    let date_index = column_index(&layout.date_column)?;
+    let mut field_indices = [None; 7];
+    for (field, column) in layout.columns().iter().enumerate() {
+        if let Some(column) = column {
+            field_indices[field] = Some(column_index(column)?);
+        }
+    }
+
+    let mut issues = Vec::new();
+    let mut days: BTreeMap<NaiveDate, RawDay> = BTreeMap::new();
+    for row in rows {
+        let row = row?;
+        let date_cell = row.get(date_index).unwrap_or("");
+        let Ok(date) = NaiveDate::parse_from_str(date_cell, &layout.date_format) else {
+            issues.push(QcIssue {
+                date: None,
+                flag: QcFlag::InvalidDate {
+                    line: row.position().map_or(0, |p| p.line()),
+                },
+            });
+            continue;
+        };
+
+        if days.contains_key(&date) {
+            issues.push(QcIssue {
+                date: Some(date),
+                flag: QcFlag::DuplicateDate,
+            });
+            continue;
+        }
+
+        let mut day = RawDay::default();
+        for (field, index) in field_indices.iter().enumerate() {
+            let Some(index) = index else { continue };
+            let cell = row.get(*index).unwrap_or("");
+            if layout.missing_values.iter().any(|m| m == cell) {
+                continue;
+            }
+            if field == PRECIPITATION && cell == "T" {
+                day.values[field] = Some(0.0);
+                continue;
+            }
+            match cell.parse::<f32>() {
+                Ok(value) => day.values[field] = Some(layout.convert(field, value)),
+                Err(_) => issues.push(QcIssue {
+                    date: Some(date),
+                    flag: QcFlag::InvalidValue {
+                        field: FIELDS[field],
+                    },
+                }),
+            }
+        }
+        check_day(date, &mut day, &mut issues);
+        days.insert(date, day);
+    }
+
+    // Every date from the first to the last row
+    let mut dates = Vec::new();
+    let mut values = Vec::new();
+    if let (Some(&first), Some(&last)) = (days.keys().next(), days.keys().next_back()) {
+        for date in first.iter_days().take_while(|d| *d <= last) {
+            let day = days.get(&date).copied().unwrap_or_else(|| {
+                issues.push(QcIssue {
+                    date: Some(date),
+                    flag: QcFlag::MissingDate,
+                });
+                RawDay::default()
+            });
+            dates.push(date);
+            values.push(day);
+        }
+    }
+
+    let mut filled_temperature = vec![false; dates.len()];
+    for field in (0..FIELDS.len()).filter(|f| *f != PRECIPITATION && field_indices[*f].is_some()) {
+        let mut series: Vec<Option<f32>> = values.iter().map(|d| d.values[field]).collect();
+        let filled = match gap_fill {
+            GapFill::None => Vec::new(),
+            GapFill::Linear { max_gap_days } => fill_linear(&mut series, max_gap_days),
+            GapFill::Climatology => fill_climatology(&mut series, &dates),
+        };
+        for index in filled {
+            values[index].values[field] = series[index];
+            filled_temperature[index] |= field <= 1; // max_temp or min_temp
+            issues.push(QcIssue {
+                date: Some(dates[index]),
+                flag: QcFlag::Filled {
+                    field: FIELDS[field],
+                    method: gap_fill,
+                },
+            });
+        }
+    }
+
+    // Temperatures are filled one at a time, so a filled day can have the minimum above the maximum
+    for (index, day) in values.iter_mut().enumerate() {
+        if filled_temperature[index] {
+            check_temperatures(dates[index], day, &mut issues);
+        }
+    }
+
+    let mut records = Vec::new();
+    for (date, day) in dates.iter().zip(values.iter()) {
+        for (field, index) in field_indices.iter().enumerate() {
+            if index.is_some() && day.values[field].is_none() {
+                issues.push(QcIssue {
+                    date: Some(*date),
+                    flag: QcFlag::MissingValue {
+                        field: FIELDS[field],
+                    },
+                });
+            }
+        }
+        if let Some(record) = to_record(*date, day, layout) {
+            records.push(record);
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.date);
+    Ok(WeatherSeries { records, issues })
+}
+
+// Removes implausible values of a day and flags them.
+fn check_day(date: NaiveDate, day: &mut RawDay, issues: &mut Vec<QcIssue>) {
+    const RANGES: [(f32, f32); 7] = [
+        (-60.0, 60.0),
+        (-60.0, 60.0),
+        (0.0, 40.0),
+        (0.0, 100.0),
+        (-60.0, 60.0),
+        (0.0, 500.0),
+        (0.0, 25.0),
+    ];
+
+    for (field, (low, high)) in RANGES.iter().enumerate() {
+        if let Some(value) = day.values[field] {
+            if value < *low || value > *high {
+                issues.push(QcIssue {
+                    date: Some(date),
+                    flag: QcFlag::OutOfRange {
+                        field: FIELDS[field],
+                        value,
+                    },
+                });
+                day.values[field] = None;
+            }
+        }
+    }
+
+    check_temperatures(date, day, issues);
+}
+
+// Removes both temperatures of a day with the minimum above the maximum and flags them.
+fn check_temperatures(date: NaiveDate, day: &mut RawDay, issues: &mut Vec<QcIssue>) {
+    if let (Some(max_temp), Some(min_temp)) = (day.values[0], day.values[1]) {
+        if min_temp > max_temp {
+            issues.push(QcIssue {
+                date: Some(date),
+                flag: QcFlag::MinAboveMax { max_temp, min_temp },
+            });
+            day.values[0] = None;
+            day.values[1] = None;
+        }
+    }
+}
+
+// Interpolates gaps of up to `max_gap_days` between two values, returning the filled indices.
+fn fill_linear(series: &mut [Option<f32>], max_gap_days: usize) -> Vec<usize> {
+    let mut filled = Vec::new();
+    let mut last_value: Option<usize> = None;
+
+    for index in 0..series.len() {
+        let Some(value) = series[index] else { continue };
+        if let Some(start) = last_value {
+            let gap = index - start - 1;
+            if gap > 0 && gap <= max_gap_days {
+                let start_value = series[start].unwrap();
+                for (step, missing) in (start + 1..index).enumerate() {
+                    let fraction = (step + 1) as f32 / (gap + 1) as f32;
+                    series[missing] = Some(start_value + fraction * (value - start_value));
+                    filled.push(missing);
+                }
+            }
+        }
+        last_value = Some(index);
+    }
+
+    filled
+}
+
+// Fills missing values with the mean of the same calendar day in the record, returning the filled indices.
+fn fill_climatology(series: &mut [Option<f32>], dates: &[NaiveDate]) -> Vec<usize> {
+    let mut sums: HashMap<(u32, u32), (f32, u32)> = HashMap::new();
+    for (value, date) in series.iter().zip(dates) {
+        if let Some(value) = value {
+            let sum = sums.entry((date.month(), date.day())).or_insert((0.0, 0));
+            sum.0 += value;
+            sum.1 += 1;
+        }
+    }
+
+    let mut filled = Vec::new();
+    for (index, date) in dates.iter().enumerate() {
+        if series[index].is_none() {
+            if let Some((sum, count)) = sums.get(&(date.month(), date.day())) {
+                series[index] = Some(sum / *count as f32);
+                filled.push(index);
+            }
+        }
+    }
+    filled
+}
+
+// Record of a day with both temperatures.
+fn to_record(date: NaiveDate, day: &RawDay, layout: &WeatherCsvLayout) -> Option<WeatherRecord> {
+    let [max_temp, min_temp, wind_speed, rh_min, dew_point, precipitation, eto] = day.values;
+    let mut record = WeatherRecord::new(date, max_temp?, min_temp?);
+
+    if let Some(wind_speed) = wind_speed {
+        record = record.with_wind_speed(wind_speed, layout.wind_height);
+    }
+    record.rh_min = rh_min;
+    record.dew_point = dew_point;
+    record.precipitation = precipitation;
+    record.eto = eto;
+    Some(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_read_weather_csv_with_units() {
+        let csv = "\
+Station: Test
+date,tmax,tmin,wind,rhmin,rain
+2023-07-01,86,59,10,40,0.5
+2023-07-02,95.0,68.0,5,35,T
+";
+        let layout = WeatherCsvLayout {
+            temperature_unit: TemperatureUnit::Fahrenheit,
+            depth_unit: DepthUnit::Inches,
+            skip_lines: 1,
+            ..WeatherCsvLayout::new("date", "tmax", "tmin")
+        }
+        .with_wind_speed("wind", SpeedUnit::MilesPerHour, 2.0)
+        .with_rh_min("rhmin")
+        .with_precipitation("rain");
+
+        let series = read_weather_csv(csv.as_bytes(), &layout, GapFill::None).unwrap();
+
+        assert!(series.issues.is_empty());
+        assert_eq!(series.records.len(), 2);
+        let first = series.records[0];
+        assert!((first.max_temp - 30.0).abs() < 0.001);
+        assert!((first.min_temp - 15.0).abs() < 0.001);
+        assert!((first.wind_speed.unwrap() - 4.4704).abs() < 0.001);
+        assert_eq!(first.rh_min, Some(40.0));
+        assert!((first.precipitation.unwrap() - 12.7).abs() < 0.001);
+        assert_eq!(series.records[1].precipitation, Some(0.0));
+    }
+
+    #[test]
+    fn test_quality_control_flags() {
+        let csv = "\
+date,tmax,tmin,rhmin
+2023-07-01,30,15,40
+2023-07-01,31,16,41
+2023-07-02,14,15,120
+2023-07-04,32,x,45
+";
+        let layout = WeatherCsvLayout::new("date", "tmax", "tmin").with_rh_min("rhmin");
+        let series = read_weather_csv(csv.as_bytes(), &layout, GapFill::None).unwrap();
+
+        assert_eq!(series.flags_on(date(7, 1)), vec![QcFlag::DuplicateDate]);
+        assert_eq!(
+            series.flags_on(date(7, 2)),
+            vec![
+                QcFlag::OutOfRange {
+                    field: "rh_min",
+                    value: 120.0
+                },
+                QcFlag::MinAboveMax {
+                    max_temp: 14.0,
+                    min_temp: 15.0
+                },
+                QcFlag::MissingValue { field: "max_temp" },
+                QcFlag::MissingValue { field: "min_temp" },
+                QcFlag::MissingValue { field: "rh_min" },
+            ]
+        );
+        assert!(series.flags_on(date(7, 3)).contains(&QcFlag::MissingDate));
+        assert!(series
+            .flags_on(date(7, 4))
+            .contains(&QcFlag::InvalidValue { field: "min_temp" }));
+
+        // Only the first day has both temperatures
+        assert_eq!(series.records.len(), 1);
+        assert_eq!(series.records[0].max_temp, 30.0);
+    }
+
+    #[test]
+    fn test_invalid_date_is_flagged_and_skipped() {
+        let csv = "\
+\"Station: Test
+Elevation: 350 m\"
+date,tmax,tmin
+2023-07-01,30,15
+2023-13-45,31,16
+2023-07-02,32,17
+";
+        let layout = WeatherCsvLayout {
+            skip_lines: 1,
+            ..WeatherCsvLayout::new("date", "tmax", "tmin")
+        };
+        let series = read_weather_csv(csv.as_bytes(), &layout, GapFill::None).unwrap();
+
+        assert_eq!(series.records.len(), 2);
+        assert_eq!(
+            series.issues,
+            vec![QcIssue {
+                date: None,
+                flag: QcFlag::InvalidDate { line: 5 },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_linear_gap_fill() {
+        let csv = "\
+date,tmax,tmin,rain
+2023-07-01,30,10,1
+2023-07-02,M,M,M
+2023-07-04,36,16,0
+2023-07-05,M,M,0
+2023-07-06,M,M,0
+2023-07-07,M,M,0
+2023-07-08,20,10,0
+";
+        let layout = WeatherCsvLayout::new("date", "tmax", "tmin").with_precipitation("rain");
+        let series =
+            read_weather_csv(csv.as_bytes(), &layout, GapFill::Linear { max_gap_days: 2 }).unwrap();
+
+        // The two-day gap is filled, the three-day gap is not
+        assert_eq!(series.records.len(), 5);
+        assert_eq!(series.records[1].date, date(7, 2));
+        assert!((series.records[1].max_temp - 32.0).abs() < 0.001);
+        assert!((series.records[2].min_temp - 14.0).abs() < 0.001);
+        assert_eq!(series.records[1].precipitation, None);
+        assert!(series.flags_on(date(7, 3)).contains(&QcFlag::Filled {
+            field: "max_temp",
+            method: GapFill::Linear { max_gap_days: 2 }
+        }));
+        assert!(series
+            .flags_on(date(7, 6))
+            .contains(&QcFlag::MissingValue { field: "max_temp" }));
+    }
+
+    #[test]
+    fn test_filled_temperatures_are_checked() {
+        let csv = "\
+date,tmax,tmin
+2023-07-01,30,10
+2023-07-02,M,28
+2023-07-03,20,10
+";
+        let layout = WeatherCsvLayout::new("date", "tmax", "tmin");
+        let series =
+            read_weather_csv(csv.as_bytes(), &layout, GapFill::Linear { max_gap_days: 2 }).unwrap();
+
+        // The filled maximum of 25 °C is below the measured minimum, so the day is left out
+        assert_eq!(series.records.len(), 2);
+        let flags = series.flags_on(date(7, 2));
+        assert!(flags.contains(&QcFlag::MinAboveMax {
+            max_temp: 25.0,
+            min_temp: 28.0
+        }));
+        assert!(flags.contains(&QcFlag::MissingValue { field: "max_temp" }));
+    }
+
+    #[test]
+    fn test_climatology_gap_fill() {
+        let csv = "\
+date,tmax,tmin
+2021-07-01,30,14
+2022-07-01,34,18
+2023-07-01,M,M
+";
+        let layout = WeatherCsvLayout::new("date", "tmax", "tmin");
+        let series = read_weather_csv(csv.as_bytes(), &layout, GapFill::Climatology).unwrap();
+
+        let last = series.records.last().unwrap();
+        assert_eq!(last.date, date(7, 1));
+        assert_eq!(last.max_temp, 32.0);
+        assert_eq!(last.min_temp, 16.0);
+    }
+
+    #[test]
+    fn test_station_layouts() {
+        let csv = "\
+Date,Max Temp (F),Min Temp (F),Min RH (%),Avg Wind Speed (mph),Precip (in),ETo (in)
+07/01/2023,86,59,40,-999,0.00,0.30
+";
+        let series =
+            read_weather_csv(csv.as_bytes(), &WeatherCsvLayout::awdn(), GapFill::None).unwrap();
+        let record = series.records[0];
+        assert_eq!(record.date, date(7, 1));
+        assert!((record.eto.unwrap() - 7.62).abs() < 0.001);
+        assert_eq!(record.wind_speed, None);
+        assert_eq!(record.wind_height, 2.0);
+
+        let csv = "Date,MaxTemperature,MinTemperature,Precipitation\n2023-07-01,86,59,T\n";
+        let series =
+            read_weather_csv(csv.as_bytes(), &WeatherCsvLayout::hprcc(), GapFill::None).unwrap();
+        assert!((series.records[0].max_temp - 30.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_missing_column_is_an_error() {
+        let csv = "date,high,low\n2023-07-01,30,15\n";
+        let layout = WeatherCsvLayout::new("date", "tmax", "tmin");
+        let error = read_weather_csv(csv.as_bytes(), &layout, GapFill::None).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Column tmax not found in the weather file."
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use crate::irrigation::DepletionDay;
+use crate::kcc_gs::{CropCoefficientsGs, GrowthStage};
+use std::collections::BTreeMap;
+
+// Yield response factors (Ky) of a crop (FAO-33), the relative yield decrease per unit of relative ET deficit. The seasonal
+// factor applies to the whole season and the optional stage factors to the initial, development, mid and late stages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct YieldResponse {
+    pub seasonal: f32,
+    pub stages: Option<[f32; 4]>,
+}
+
+impl YieldResponse {
+    /// Creates a new `YieldResponse` with the seasonal Ky and the optional Ky of each growth stage. Panics if a factor is
+    /// negative.
+    pub fn new(seasonal: f32, stages: Option<[f32; 4]>) -> Self {
+        if seasonal < 0.0 || stages.is_some_and(|s| s.iter().any(|ky| *ky < 0.0)) {
+            panic!("Ky cannot be negative.");
+        }
+
+        YieldResponse { seasonal, stages }
+    }
+
+    /// Returns the Ky of a growth stage, if the stage factors are known.
+    pub fn stage(&self, growth_stage: GrowthStage) -> Option<f32> {
+        let index = GrowthStage::ALL
+            .iter()
+            .position(|s| *s == growth_stage)
+            .unwrap();
+        self.stages.map(|stages| stages[index])
+    }
+}
+
+/// Calculates the relative yield decrease (1 - Ya/Ym) from the relative ET deficit (FAO-33 Eq. 2, FAO-56 Eq. 90).
+///
+/// # Parameters
+///
+/// - `ky`: The yield response factor.
+/// - `actual_et`: The actual ET in mm.
+/// - `etc`: The potential crop ET (ETc) in mm.
+///
+/// # Returns
+///
+/// The relative yield decrease, between 0 and 1. There is no decrease without ETc.
+pub fn relative_yield_decrease(ky: f32, actual_et: f32, etc: f32) -> f32 {
+    if etc <= 0.0 {
+        return 0.0;
+    }
+    (ky * (1.0 - actual_et / etc)).clamp(0.0, 1.0)
+}
+
+// Actual and potential ET of a growth stage in mm with its Ky and relative yield decrease.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StageYieldResponse {
+    pub stage: GrowthStage,
+    pub etc: f32,
+    pub actual_et: f32,
+    pub ky: Option<f32>,
+    pub relative_yield_decrease: Option<f32>,
+}
+
+// Yield response of a season. The seasonal decrease uses the seasonal Ky with the season ET, the staged decrease combines
+// the decrease of each stage as FAO-33 does for stage deficits, Ya/Ym = Π(1 - Ky_i(1 - ETa_i/ETc_i)).
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldImpact {
+    pub stages: Vec<StageYieldResponse>,
+    pub seasonal_decrease: f32,
+    pub staged_decrease: Option<f32>,
+}
+
+impl YieldImpact {
+    /// Returns the relative yield decrease, the staged decrease when the stage factors are known and the seasonal one
+    /// otherwise.
+    pub fn relative_yield_decrease(&self) -> f32 {
+        self.staged_decrease.unwrap_or(self.seasonal_decrease)
+    }
+
+    /// Returns the relative yield (Ya/Ym).
+    pub fn relative_yield(&self) -> f32 {
+        1.0 - self.relative_yield_decrease()
+    }
+}
+
+/// Calculates the yield response of a crop to the water deficit of a season, summing the actual and potential ET of each
+/// growth stage.
+///
+/// # Parameters
+///
+/// - `cc`: The `CropCoefficientsGs` of the crop with its `YieldResponse`.
+/// - `days`: A slice of `DepletionDay` with the daily ETc and actual ET, as in the trace of `schedule_irrigation`.
+///
+/// # Returns
+///
+/// The `YieldImpact` of the season, or `None` if the crop has no yield response factors.
+pub fn yield_response(cc: &CropCoefficientsGs, days: &[DepletionDay]) -> Option<YieldImpact> {
+    let ky = cc.yield_response?;
+
+    let mut totals: BTreeMap<GrowthStage, (f32, f32)> = BTreeMap::new();
+    for day in days {
+        let total = totals
+            .entry(cc.growth_stage_on(day.date))
+            .or_insert((0.0, 0.0));
+        total.0 += day.etc;
+        total.1 += day.actual_et;
+    }
+
+    let stages: Vec<StageYieldResponse> = totals
+        .into_iter()
+        .map(|(stage, (etc, actual_et))| {
+            let stage_ky = ky.stage(stage);
+            StageYieldResponse {
+                stage,
+                etc,
+                actual_et,
+                ky: stage_ky,
+                relative_yield_decrease: stage_ky
+                    .map(|ky| relative_yield_decrease(ky, actual_et, etc)),
+            }
+        })
+        .collect();
+
+    let etc: f32 = stages.iter().map(|s| s.etc).sum();
+    let actual_et: f32 = stages.iter().map(|s| s.actual_et).sum();
+    let staged_decrease = ky.stages.map(|_| {
+        1.0 - stages
+            .iter()
+            .filter_map(|s| s.relative_yield_decrease)
+            .map(|decrease| 1.0 - decrease)
+            .product::<f32>()
+    });
+
+    Some(YieldImpact {
+        stages,
+        seasonal_decrease: relative_yield_decrease(ky.seasonal, actual_et, etc),
+        staged_decrease,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kcc_gs::load_crop_coefficients;
+    use crate::test_fixtures;
+
+    fn corn() -> CropCoefficientsGs {
+        test_fixtures::corn()
+            .with_yield_response(YieldResponse::new(1.25, Some([0.4, 0.4, 1.5, 0.5])))
+    }
+
+    // 10 mm of ETc per day through the season, with the actual ET reduced by the Ks of each stage
+    fn days(cc: &CropCoefficientsGs, ks: [f32; 4]) -> Vec<DepletionDay> {
+        (0..120)
+            .map(|day| {
+                let date = cc.planting_date + chrono::Duration::days(day);
+                let index = GrowthStage::ALL
+                    .iter()
+                    .position(|s| *s == cc.growth_stage_on(date))
+                    .unwrap();
+                DepletionDay {
+                    date,
+                    kc: 1.0,
+                    etc: 10.0,
+                    ks: ks[index],
+                    actual_et: 10.0 * ks[index],
+                    precipitation: 0.0,
+                    irrigation: 0.0,
+                    deep_percolation: 0.0,
+                    depletion: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_relative_yield_decrease() {
+        assert!((relative_yield_decrease(1.25, 400.0, 500.0) - 0.25).abs() < 0.0001);
+        assert_eq!(relative_yield_decrease(1.25, 500.0, 500.0), 0.0);
+        assert_eq!(relative_yield_decrease(1.5, 0.0, 500.0), 1.0);
+        assert_eq!(relative_yield_decrease(1.25, 0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_yield_response_by_stage() {
+        let cc = corn();
+        let impact = yield_response(&cc, &days(&cc, [1.0, 1.0, 0.8, 1.0])).unwrap();
+
+        assert_eq!(impact.stages.len(), 4);
+        let mid = impact.stages[2];
+        assert_eq!(mid.stage, GrowthStage::Mid);
+        assert!((mid.etc - 500.0).abs() < 0.01);
+        assert!((mid.relative_yield_decrease.unwrap() - 0.3).abs() < 0.0001);
+
+        // Only the mid stage is short of water
+        assert!((impact.staged_decrease.unwrap() - 0.3).abs() < 0.0001);
+        assert!((impact.relative_yield() - 0.7).abs() < 0.0001);
+
+        // 100 mm short of 1200 mm over the season
+        let seasonal = 1.25 * 100.0 / 1200.0;
+        assert!((impact.seasonal_decrease - seasonal).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_yield_response_seasonal_only() {
+        let cc = corn().with_yield_response(YieldResponse::new(1.25, None));
+        let impact = yield_response(&cc, &days(&cc, [0.8, 0.8, 0.8, 0.8])).unwrap();
+
+        assert_eq!(impact.staged_decrease, None);
+        assert!(impact.stages.iter().all(|s| s.ky.is_none()));
+        assert!((impact.relative_yield_decrease() - 0.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_yield_response_requires_ky() {
+        let mut cc = corn();
+        cc.yield_response = None;
+        assert_eq!(yield_response(&cc, &days(&cc, [1.0; 4])), None);
+    }
+
+    #[test]
+    fn test_crop_table_yield_response() {
+        let crops = load_crop_coefficients().unwrap();
+        let corn = crops["corn"].yield_response.unwrap();
+        assert_eq!(corn.seasonal, 1.25);
+        assert_eq!(corn.stage(GrowthStage::Mid), Some(1.5));
+
+        let alfalfa = crops["alfalfa"].yield_response.unwrap();
+        assert_eq!(alfalfa.stage(GrowthStage::Mid), None);
+    }
+}